@@ -0,0 +1,45 @@
+//Times `find_iter` over growing haystacks. Each search starts where the last match ended, so if finding the
+//starting point were not constant time the time per megabyte would grow with the size of the haystack.
+//The pattern can start with any character, so every position goes through `AdvancerIterator::new` rather than a prefilter
+//
+//    cargo bench --bench find_iter
+
+use native_regex_lib::interpreter::EhirInterpreter;
+use native_regex_lib::native_regex::NativeRegex;
+use std::time::Instant;
+
+const SIZES: [usize; 4] = [1, 2, 4, 8]; //Megabytes
+
+fn haystack(megabytes: usize) -> String {
+    let line = "héllo wörld 2021-04-17 ";
+
+    line.repeat(megabytes * 1024 * 1024 / line.len())
+}
+
+fn main() {
+    let regex = EhirInterpreter::new(r"(?s:.)[0-9]{4}-[0-9]{2}-[0-9]{2}").unwrap();
+
+    assert!(regex.literal_prefix().is_empty() && regex.first_characters().is_none() && !regex.is_ascii());
+
+    let mut per_megabyte = Vec::new();
+
+    for megabytes in SIZES.iter() {
+        let text = haystack(*megabytes);
+
+        let start = Instant::now();
+        let count = regex.find_iter(text.as_str()).count();
+        let elapsed = start.elapsed();
+
+        per_megabyte.push(elapsed.as_secs_f64() / *megabytes as f64);
+
+        println!("{} MB: {} matches in {:?} ({:?} per MB)", megabytes, count, elapsed, elapsed / *megabytes as u32);
+    }
+
+    //Quadratic behaviour would make the largest haystack 8 times slower per megabyte than the smallest
+    let ratio = per_megabyte[per_megabyte.len() - 1] / per_megabyte[0];
+
+    println!("time per MB grew by {:.2}x from {} MB to {} MB", ratio, SIZES[0], SIZES[SIZES.len() - 1]);
+
+    //Loose enough for a busy machine, while quadratic behaviour would still fail it
+    assert!(ratio < 3.0, "find_iter is not linear in the size of the haystack");
+}
@@ -0,0 +1,96 @@
+//Compares the byte stepping `step_bytes` of ASCII only regexes against decoding UTF-8 with `step`, by `find_iter`
+//throughput over fixed ASCII log lines
+//
+//    cargo bench -p native-regex-macros --bench ascii
+
+use native_regex_lib::native_regex::{CaptureNames, NativeRegex};
+use native_regex_lib::native_regex::character::{Advancer, CharacterSet};
+use native_regex_macros::native_regex;
+use std::time::Instant;
+
+native_regex!(DateRegex = r"[0-9]{4}-[0-9]{2}-[0-9]{2}");
+native_regex!(AssignmentRegex = r"[a-z]*=[0-9]+");
+
+const RUNS: usize = 5;
+
+//The same regex with `is_ascii` turned off, so the search decodes UTF-8 and calls `step`
+struct Chars<R>(R);
+
+impl<R: NativeRegex> NativeRegex for Chars<R> {
+    type Locations = R::Locations;
+
+    fn step(&self, chars: Advancer, captures: & mut Self::Locations) -> Option<()> {
+        self.0.step(chars, captures)
+    }
+
+    fn capture_names(&self) -> & CaptureNames {
+        self.0.capture_names()
+    }
+
+    fn capture_count(&self) -> usize {
+        self.0.capture_count()
+    }
+
+    fn literal_prefix(&self) -> &str {
+        self.0.literal_prefix()
+    }
+
+    fn first_characters(&self) -> Option<& CharacterSet> {
+        self.0.first_characters()
+    }
+
+    fn min_length(&self) -> usize {
+        self.0.min_length()
+    }
+
+    fn max_length(&self) -> Option<usize> {
+        self.0.max_length()
+    }
+
+    fn is_anchored_start(&self) -> bool {
+        self.0.is_anchored_start()
+    }
+
+    fn is_anchored_line_start(&self) -> bool {
+        self.0.is_anchored_line_start()
+    }
+}
+
+fn corpus() -> String {
+    let line = "2021-04-17 12:03:44 INFO request id=1842 user=ray status=200 bytes=5120 elapsed=37ms path=/index.html\n";
+
+    line.repeat(8 * 1024 * 1024 / line.len())
+}
+
+//The best of several runs, in MB/s
+fn throughput<R: NativeRegex>(regex: & R, text: & str) -> f64 {
+    let mut best = f64::MAX;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let count = regex.find_iter(text).count();
+
+        assert!(count > 0);
+
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+
+    text.len() as f64 / best / 1e6
+}
+
+fn report<R: NativeRegex + Clone>(pattern: & str, regex: & R, text: & str) {
+    assert!(regex.is_ascii());
+
+    println!("| `{}` | {:.0} MB/s | {:.0} MB/s |", pattern, throughput(& Chars(regex.clone()), text), throughput(regex, text));
+}
+
+fn main() {
+    let text = corpus();
+
+    println!("{:.1}MB corpus, best of {} runs\n", text.len() as f64 / 1e6, RUNS);
+    println!("| Pattern | Throughput with `step` | Throughput with `step_bytes` |");
+    println!("|---|---|---|");
+
+    report(r"[0-9]{4}-[0-9]{2}-[0-9]{2}", & DateRegex::new(), text.as_str());
+    report(r"[a-z]*=[0-9]+", & AssignmentRegex::new(), text.as_str());
+}
@@ -0,0 +1,66 @@
+//Compares character classes matched inline with `matches!` patterns against the same classes looked up in a static
+//range table, by the size of the generated code and by `find_iter` throughput over a fixed mixed script corpus
+//
+//    cargo bench -p native-regex-macros --bench range_table
+
+use native_regex_lib::native_regex::NativeRegex;
+use native_regex_lib::options::TranslateOptions;
+use native_regex_lib::rust_translate::translate_with_options;
+use native_regex_macros::native_regex;
+use std::time::Instant;
+
+native_regex!(InlineWordRegex = r"\w+", range_table_threshold = 100000);
+native_regex!(TableWordRegex = r"\w+");
+native_regex!(InlineLetterRegex = r"\p{L}+", range_table_threshold = 100000);
+native_regex!(TableLetterRegex = r"\p{L}+");
+native_regex!(InlineDigitRegex = r"\d+", range_table_threshold = 100000);
+native_regex!(TableDigitRegex = r"\d+");
+native_regex!(NameRegex = r"[A-Z][a-z]+"); //Small enough to stay inline with either setting
+
+const RUNS: usize = 5;
+
+fn corpus() -> String {
+    let line = "The quick brown fox, jumps over 13 lazy dogs! Ça va très bien, ありがとう ございます. Привет мир; αβγ δεζ. ";
+
+    line.repeat(8 * 1024 * 1024 / line.len())
+}
+
+fn code_size(pattern: & str, range_table_threshold: usize) -> usize {
+    translate_with_options(pattern, "SizeRegex", & TranslateOptions { range_table_threshold, ..TranslateOptions::default() }).unwrap().len()
+}
+
+//The best of several runs, in MB/s
+fn throughput<R: NativeRegex>(regex: & R, text: & str) -> f64 {
+    let mut best = f64::MAX;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let count = regex.find_iter(text).count();
+
+        assert!(count > 0);
+
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+
+    text.len() as f64 / best / 1e6
+}
+
+fn report<I: NativeRegex, T: NativeRegex>(pattern: & str, inline: & I, table: & T, text: & str) {
+    println!("| `{}` | {:.1}KB | {:.1}KB | {:.0} MB/s | {:.0} MB/s |", pattern,
+        code_size(pattern, usize::MAX) as f64 / 1000.0, code_size(pattern, 0) as f64 / 1000.0,
+        throughput(inline, text), throughput(table, text));
+}
+
+fn main() {
+    let text = corpus();
+
+    println!("{:.1}MB corpus, best of {} runs\n", text.len() as f64 / 1e6, RUNS);
+    println!("| Pattern | Code inline | Code with table | Throughput inline | Throughput with table |");
+    println!("|---|---|---|---|---|");
+
+    report(r"\w+", & InlineWordRegex::new(), & TableWordRegex::new(), text.as_str());
+    report(r"\p{L}+", & InlineLetterRegex::new(), & TableLetterRegex::new(), text.as_str());
+    report(r"\d+", & InlineDigitRegex::new(), & TableDigitRegex::new(), text.as_str());
+
+    println!("| `[A-Z][a-z]+` | {:.1}KB | - | {:.0} MB/s | - |", code_size(r"[A-Z][a-z]+", usize::MAX) as f64 / 1000.0, throughput(& NameRegex::new(), text.as_str()));
+}
@@ -0,0 +1,125 @@
+use native_regex_lib::options::TranslateOptions;
+use proc_macro::TokenStream;
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Ident, Item, Lit, LitStr, Token, Visibility};
+
+//The input to `native_regex!`, `vis Name = "regex", option, option = bool, ...`
+struct NativeRegexInput {
+    visibility: Visibility,
+    name: Ident,
+    regex: LitStr,
+    options: TranslateOptions,
+}
+
+//Set the `TranslateOptions` field named by `option`
+fn set_option(options: & mut TranslateOptions, option: & Ident, value: Option<Lit>) -> syn::Result<()> {
+    //The only numeric option
+    if option == "range_table_threshold" {
+        options.range_table_threshold = match value {
+            Some(Lit::Int(value)) => value.base10_parse()?,
+            _ => return Err(syn::Error::new(option.span(), format!("option '{}' needs a number", option))),
+        };
+
+        return Ok(());
+    }
+
+    let flag = match option.to_string().as_str() {
+        "backtracking" => & mut options.backtracking,
+        "deny_possessive_warnings" => & mut options.deny_possessive_warnings,
+        "case_insensitive" => & mut options.case_insensitive,
+        "multi_line" => & mut options.multi_line,
+        "dot_matches_new_line" => & mut options.dot_matches_new_line,
+        "swap_greed" => & mut options.swap_greed,
+        "ignore_whitespace" => & mut options.ignore_whitespace,
+        "unicode" => & mut options.unicode,
+        _ => return Err(syn::Error::new(option.span(), format!("unknown option '{}'", option))),
+    };
+
+    *flag = match value {
+        None => true,
+        Some(Lit::Bool(value)) => value.value,
+        Some(value) => return Err(syn::Error::new(value.span(), format!("option '{}' needs a bool", option))),
+    };
+
+    Ok(())
+}
+
+impl Parse for NativeRegexInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let visibility = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let regex = input.parse()?;
+
+        //Options are named by their `TranslateOptions` field, and flags are set to true unless given a value
+        let mut options = TranslateOptions::default();
+
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+
+            if !input.peek(Ident) {
+                break;
+            }
+
+            let option = input.parse::<Ident>()?;
+
+            let value = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(input.parse::<Lit>()?)
+            } else {
+                None
+            };
+
+            set_option(& mut options, & option, value)?;
+        }
+
+        //Allow a trailing semicolon, so the macro reads like an item
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+
+        Ok(NativeRegexInput {
+            visibility,
+            name,
+            regex,
+            options,
+        })
+    }
+}
+
+//Generates a NativeRegex struct at compile time
+//
+//    native_regex!(pub IpRegex = r"([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})");
+//    native_regex!(KeywordRegex = r"select|from", case_insensitive, unicode = false);
+//    native_regex!(WordRegex = r"\w+", range_table_threshold = 32);
+//
+//expands to the struct and its `NativeRegex` impl, exactly as `rust_translate::translate_with_options` would generate.
+//Any `TranslateOptions` field can follow the pattern. Flags are set to true unless given a value. The generated code
+//refers to `native_regex_lib`, so the calling crate must depend on it. Regexes that fail to translate are reported
+//as compile errors pointing at the regex literal
+#[proc_macro]
+pub fn native_regex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as NativeRegexInput);
+
+    let code = match native_regex_lib::rust_translate::translate_with_options(input.regex.value().as_str(), input.name.to_string().as_str(), & input.options) {
+        Ok(code) => code,
+        Err(message) => return syn::Error::new(input.regex.span(), message).to_compile_error().into(),
+    };
+
+    let mut file = match syn::parse_file(code.as_str()) {
+        Ok(file) => file,
+        Err(error) => return syn::Error::new(input.regex.span(), format!("generated code does not parse: {}", error)).to_compile_error().into(),
+    };
+
+    //The translator always generates a `pub` struct, so swap in the visibility that was asked for
+    for item in file.items.iter_mut() {
+        if let Item::Struct(item_struct) = item {
+            if item_struct.ident == input.name {
+                item_struct.vis = input.visibility.clone();
+            }
+        }
+    }
+
+    file.into_token_stream().into()
+}
@@ -0,0 +1,331 @@
+use native_regex_lib::differential::{compare, Outcome};
+use native_regex_lib::interpreter::EhirInterpreter;
+use native_regex_lib::native_regex::NativeRegex;
+use native_regex_lib::native_regex::captures::Captures;
+use native_regex_lib::native_regex::replacer::NoExpand;
+use native_regex_lib::native_regex::native_regex_set::NativeRegexSet;
+use native_regex_lib::native_regex::character::{AdvancerIterator, ByteAdvancer};
+use native_regex_lib::vectormap::{ArrayMap, CaptureLocations};
+use native_regex_macros::native_regex;
+use regex::Regex;
+
+native_regex!(pub IpRegex = r"([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})");
+native_regex!(DateRegex = r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})");
+native_regex!(pub(crate) TagRegex = r"<.+?>";);
+
+mod nested {
+    native_regex_macros::native_regex!(pub WordRegex = r"\w+");
+}
+
+const CORPUS: [& str; 6] = [
+    "",
+    "192.168.0.1 and 10.0.0.255",
+    "2021-04-17 and 1999-12-31",
+    "<a><bb>",
+    "héllo wörld",
+    "1.2.3",
+];
+
+fn assert_agrees<R: NativeRegex>(native: & R, pattern: & str) {
+    assert_agrees_on(native, pattern, & CORPUS);
+}
+
+fn assert_agrees_on<R: NativeRegex>(native: & R, pattern: & str, corpus: & [& str]) {
+    let divergences = compare(native, & Regex::new(pattern).unwrap(), corpus);
+
+    for divergence in divergences.iter() {
+        println!("{}: {}", pattern, divergence);
+    }
+
+    assert!(divergences.is_empty(), "{} diverged from regex", pattern);
+}
+
+#[test]
+fn generated_regexes_agree() {
+    assert_agrees(& IpRegex::new(), r"([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})");
+    assert_agrees(& DateRegex::new(), r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})");
+    assert_agrees(& TagRegex::new(), r"<.+?>");
+    assert_agrees(& nested::WordRegex::new(), r"\w+");
+}
+
+#[test]
+fn named_groups() {
+    let captures = DateRegex::new().captures("on 2021-04-17").unwrap();
+
+    assert_eq!(captures.name("year").map(|m| m.as_str()), Some("2021"));
+    assert_eq!(captures.name("day").map(|m| m.as_str()), Some("17"));
+}
+
+native_regex!(AlternationRegex = r"(?:(a)x|a(b))c|ab");
+
+//A failed branch gives back the characters and groups it matched before the next branch is tried
+#[test]
+fn alternation() {
+    let captures = AlternationRegex::new().captures("abc").unwrap();
+
+    assert_eq!(captures.get(0).map(|m| m.range()), Some(0..3));
+    assert_eq!(captures.get(1).map(|m| m.range()), None);
+    assert_eq!(captures.get(2).map(|m| m.range()), Some(1..2));
+
+    assert_eq!(AlternationRegex::new().find("abd").map(|m| m.range()), Some(0..2));
+
+    assert_agrees_on(& AlternationRegex::new(), r"(?:(a)x|a(b))c|ab", & ["abc", "axc", "abd", "axabc"]);
+}
+
+native_regex!(RewindRegex = r"(ab)*c");
+
+//An iteration that fails part way gives back what it consumed, so the search finds the 'c' at the end
+#[test]
+fn failed_iterations_rewind() {
+    let captures = RewindRegex::new().captures("ababac").unwrap();
+
+    assert_eq!(captures.get(0).map(|m| m.range()), Some(5..6));
+    assert_eq!(captures.get(1).map(|m| m.range()), None);
+
+    assert_eq!(RewindRegex::new().find("ababc").map(|m| m.range()), Some(0..5));
+
+    assert_agrees_on(& RewindRegex::new(), r"(ab)*c", & ["ababac", "ababc", "abac", "c"]);
+}
+
+native_regex!(DiscardedIterationRegex = r"(?:(a)b)*a");
+native_regex!(SkippedOptionalRegex = r"(?:(a)b)?a");
+
+//Groups recorded by an iteration that failed, or by an optional group that was skipped, are not reported
+#[test]
+fn discarded_captures() {
+    let captures = DiscardedIterationRegex::new().captures("aba").unwrap();
+
+    assert_eq!(captures.get(0).map(|m| m.range()), Some(0..3));
+    assert_eq!(captures.get(1).map(|m| m.range()), Some(0..1));
+
+    let captures = SkippedOptionalRegex::new().captures("a").unwrap();
+
+    assert_eq!(captures.get(0).map(|m| m.range()), Some(0..1));
+    assert_eq!(captures.get(1).map(|m| m.range()), None);
+
+    assert_agrees_on(& DiscardedIterationRegex::new(), r"(?:(a)b)*a", & ["aba", "a", "ababa", "abaa"]);
+    assert_agrees_on(& SkippedOptionalRegex::new(), r"(?:(a)b)?a", & ["a", "aba", "aa"]);
+}
+
+native_regex!(QuotedRegex = r#"".*?""#);
+
+//Lazy repetition stops at the first closing delimiter instead of the last
+#[test]
+fn lazy() {
+    let tags: Vec<_> = TagRegex::new().find_iter("<a><bb>").map(|m| m.as_str()).collect();
+    let quoted: Vec<_> = QuotedRegex::new().find_iter(r#"say "hi" and "bye""#).map(|m| m.as_str()).collect();
+
+    assert_eq!(tags, ["<a>", "<bb>"]);
+    assert_eq!(quoted, [r#""hi""#, r#""bye""#]);
+
+    assert_agrees_on(& QuotedRegex::new(), r#"".*?""#, & [r#"say "hi" and "bye""#, r#""""#, r#""open"#, "\"a\nb\""]);
+}
+
+native_regex!(DigitsRegex = r"([0-9]*)([0-9])");
+native_regex!(BacktrackingDigitsRegex = r"([0-9]*)([0-9])", backtracking);
+
+//The readme's example, where the possessive `[0-9]*` takes every digit and leaves none for `[0-9]`
+#[test]
+fn backtracking() {
+    assert!(!DigitsRegex::new().is_match("2021"));
+
+    let captures = BacktrackingDigitsRegex::new().captures("2021").unwrap();
+
+    assert_eq!(captures.get(1).map(|m| m.as_str()), Some("202"));
+    assert_eq!(captures.get(2).map(|m| m.as_str()), Some("1"));
+
+    assert_agrees(& BacktrackingDigitsRegex::new(), r"([0-9]*)([0-9])");
+}
+
+fn outcomes<R: NativeRegex>(native: & R, text: & str) -> [Outcome; 3] {
+    [
+        Outcome::Find(native.find(text).map(|m| (m.start(), m.end()))),
+        Outcome::Captures(native.captures(text).map(|captures| captures.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect())),
+        Outcome::FindIter(native.find_iter(text).map(|m| (m.start(), m.end())).collect()),
+    ]
+}
+
+fn assert_interpreter_agrees<R: NativeRegex>(native: & R, pattern: & str, corpus: & [& str]) {
+    let interpreter = EhirInterpreter::new(pattern).unwrap();
+
+    for text in corpus {
+        assert_eq!(outcomes(& interpreter, text), outcomes(native, text), "{} on {:?}", pattern, text);
+    }
+}
+
+//The interpreter runs the same tokens as the generated code, so it agrees even where both differ from `regex`
+#[test]
+fn interpreter_agrees() {
+    let corpus = [& CORPUS[..], & ["2021", "abab", "ababa", r#"say "hi" and "bye""#]].concat();
+
+    assert_interpreter_agrees(& IpRegex::new(), r"([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})", & corpus);
+    assert_interpreter_agrees(& DateRegex::new(), r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})", & corpus);
+    assert_interpreter_agrees(& TagRegex::new(), r"<.+?>", & corpus);
+    assert_interpreter_agrees(& AlternationRegex::new(), r"(?:(a)x|a(b))c|ab", & corpus);
+    assert_interpreter_agrees(& DiscardedIterationRegex::new(), r"(?:(a)b)*a", & corpus);
+    assert_interpreter_agrees(& QuotedRegex::new(), r#"".*?""#, & corpus);
+    assert_interpreter_agrees(& DigitsRegex::new(), r"([0-9]*)([0-9])", & corpus);
+    assert_interpreter_agrees(& EmptyGroupsRegex::new(), r"(a*)*", & corpus);
+}
+
+native_regex!(KeywordRegex = r"select|from", case_insensitive);
+native_regex!(AsciiWordRegex = r"\b\w+\b", unicode = false);
+native_regex!(CommentedRegex = r"
+    (\d+)   # whole part
+    \.
+    (\d+)   # fractional part
+", ignore_whitespace);
+
+native_regex!(EmptyLoopRegex = r"((?:\d*)*-)*\d");
+native_regex!(BacktrackingEmptyLoopRegex = r"((?:\d*)*-)*\d", backtracking);
+native_regex!(BacktrackingLazyEmptyLoopRegex = r"(?:[a-z]*|<)*?>", backtracking);
+native_regex!(LazyMinimumRegex = r"(?:a*){2,}?");
+native_regex!(BacktrackingLazyMinimumRegex = r"(?:a*){2,}?", backtracking);
+
+#[test]
+fn empty_repetitions() {
+    assert_agrees(& EmptyLoopRegex::new(), r"((?:\d*)*-)*\d");
+    assert_agrees(& BacktrackingEmptyLoopRegex::new(), r"((?:\d*)*-)*\d");
+    assert_agrees(& BacktrackingLazyEmptyLoopRegex::new(), r"(?:[a-z]*|<)*?>");
+    assert_agrees(& LazyMinimumRegex::new(), r"(?:a*){2,}?");
+    assert_agrees(& BacktrackingLazyMinimumRegex::new(), r"(?:a*){2,}?");
+    assert!(LazyMinimumRegex::new().is_match(""));
+}
+
+native_regex!(EmptyRegex = r"x*");
+native_regex!(EndRegex = r"$");
+native_regex!(LineEndRegex = r"(?m)$");
+native_regex!(NotBoundaryRegex = r"\B");
+native_regex!(EmptyGroupsRegex = r"(a*)*");
+native_regex!(BacktrackingEmptyGroupsRegex = r"(a*)*", backtracking);
+
+//Empty matches at the end of the text, including the whole of an empty text
+#[test]
+fn empty_matches_at_end() {
+    assert_agrees(& EmptyRegex::new(), r"x*");
+    assert_agrees(& EndRegex::new(), r"$");
+    assert_agrees(& LineEndRegex::new(), r"(?m)$");
+    assert_agrees(& NotBoundaryRegex::new(), r"\B");
+    assert_agrees(& EmptyGroupsRegex::new(), r"(a*)*");
+    assert_agrees(& BacktrackingEmptyGroupsRegex::new(), r"(a*)*");
+}
+
+native_regex!(ManyGroupsRegex = r"([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?([a-z])?");
+
+#[test]
+fn many_groups() {
+    let captures = ManyGroupsRegex::new().captures("abc").unwrap();
+
+    assert_eq!(captures.len(), 81);
+    assert_eq!(captures.get(3).map(|m| m.as_str()), Some("c"));
+    assert!(captures.get(80).is_none());
+}
+
+//The set sizes its storage for the many groups, beyond what the `ArrayMap` of `IpRegex` holds
+#[test]
+fn mixed_group_counts_in_a_set() {
+    let set = NativeRegexSet::new(vec![ManyGroupsRegex::new().engine(), IpRegex::new().engine()]);
+
+    let matches = set.matches("abc 10.0.0.1");
+    let mut found: Vec<_> = matches.iter().map(|(index, captures)| (*index, captures.get(0).unwrap().as_str())).collect();
+
+    found.sort();
+
+    assert_eq!(found, vec![(0, "abc"), (1, "10.0.0.1")]);
+    assert!(set.is_match("10.0.0.1"));
+}
+
+#[test]
+fn fixed_size_locations() {
+    //Regexes with 64 or fewer groups keep their locations on the stack
+    let captures = IpRegex::new().captures("127.0.0.1").unwrap();
+    let locations: ArrayMap<(usize, usize), 5> = captures.locations;
+
+    assert_eq!(locations.get(4), Some(& (8, 9)));
+}
+
+//Replacements read the captures in each regex's own storage, an `ArrayMap` for `DateRegex` and a `VectorMap` for `ManyGroupsRegex`
+#[test]
+fn replace() {
+    let date = DateRegex::new();
+
+    assert_eq!(date.replace("on 2021-04-17", "${day}/${month}/${year}"), "on 17/04/2021");
+    assert_eq!(date.replace("on 2021-04-17", |caps: & Captures<_>| caps.name("year").unwrap().as_str().to_string()), "on 2021");
+    assert_eq!(date.replace("on 2021-04-17", NoExpand("${day}")), "on ${day}");
+    assert_eq!(ManyGroupsRegex::new().replace("ab", "${2}${1}"), "ba");
+}
+
+native_regex!(EscapedRegex = r#"['\\\n"\u{301}]+\t?\x00?"#);
+native_regex!(TableWordRegex = r"\w+");
+native_regex!(InlineWordRegex = r"\w+", range_table_threshold = 100000);
+native_regex!(TableNameRegex = r"(?i)[a-zé][a-z0-9_é]*", range_table_threshold = 0);
+
+#[test]
+fn options() {
+    assert_eq!(KeywordRegex::new().find("SELECT * FROM t").map(|m| m.as_str()), Some("SELECT"));
+    assert_agrees(& AsciiWordRegex::new(), r"(?-u)\b\w+\b");
+    assert_eq!(CommentedRegex::new().captures("pi is 3.14").unwrap().get(2).map(|m| m.as_str()), Some("14"));
+    assert_eq!(EscapedRegex::new().find("a'\\\n\"\u{301}\t\0b").map(|m| m.as_str()), Some("'\\\n\"\u{301}\t\0"));
+    assert_agrees(& TableWordRegex::new(), r"\w+");
+    assert_agrees(& InlineWordRegex::new(), r"\w+");
+    assert_agrees(& TableNameRegex::new(), r"(?i)[a-zé][a-z0-9_é]*");
+}
+
+native_regex!(AsciiClassRegex = r"[a-z]+[0-9]?");
+native_regex!(AsciiBoundaryRegex = r"\b[a-z]+\b", unicode = false);
+native_regex!(AsciiEmptyRegex = r"x*(y)?");
+native_regex!(AsciiLineRegex = r"(?m)^[a-z]+$");
+native_regex!(AsciiPrefixRegex = r"id=([0-9]+)");
+native_regex!(AsciiBacktrackingRegex = r"([a-z]*)([a-z])-", backtracking);
+
+const NON_ASCII_CORPUS: [& str; 6] = [
+    "",
+    "héllo wörld",
+    "añb\ncafé\nabc\u{301}\nok",
+    "id=12é id=3 ü-id=",
+    "x\u{1f600}xy é ab- c\u{301}d-",
+    "\u{e9}\u{e9}x",
+];
+
+//Stepping over bytes must give the same result as decoding chars, at every position a match can start
+fn assert_bytes_agree<R: NativeRegex>(native: & R) {
+    assert!(native.is_ascii());
+
+    for text in NON_ASCII_CORPUS.iter() {
+        for position in (0..=text.len()).filter(|position| text.is_char_boundary(*position)) {
+            let mut chars = R::Locations::with_groups(native.capture_count());
+            let mut bytes = R::Locations::with_groups(native.capture_count());
+
+            let chars_matched = native.step(AdvancerIterator::new(text, position).next().unwrap(), & mut chars);
+            let bytes_matched = native.step_bytes(ByteAdvancer::new(text, position), & mut bytes);
+
+            assert_eq!(chars_matched, bytes_matched, "{:?} at {}", text, position);
+
+            if chars_matched.is_some() {
+                for index in 0..native.capture_count() {
+                    assert_eq!(chars.get(index), bytes.get(index), "{:?} at {}", text, position);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn ascii_regexes() {
+    assert_bytes_agree(& AsciiClassRegex::new());
+    assert_bytes_agree(& AsciiBoundaryRegex::new());
+    assert_bytes_agree(& AsciiEmptyRegex::new());
+    assert_bytes_agree(& AsciiLineRegex::new());
+    assert_bytes_agree(& AsciiPrefixRegex::new());
+    assert_bytes_agree(& AsciiBacktrackingRegex::new());
+
+    //The search steps over bytes as well, and only tries char boundaries
+    assert_agrees_on(& AsciiClassRegex::new(), r"[a-z]+[0-9]?", & NON_ASCII_CORPUS);
+    assert_agrees_on(& AsciiBoundaryRegex::new(), r"(?-u)\b[a-z]+\b", & NON_ASCII_CORPUS);
+    assert_agrees_on(& AsciiEmptyRegex::new(), r"x*(y)?", & NON_ASCII_CORPUS);
+    assert_agrees_on(& AsciiLineRegex::new(), r"(?m)^[a-z]+$", & NON_ASCII_CORPUS);
+    assert_agrees_on(& AsciiPrefixRegex::new(), r"id=([0-9]+)", & NON_ASCII_CORPUS);
+    assert_agrees_on(& AsciiBacktrackingRegex::new(), r"([a-z]*)([a-z])-", & NON_ASCII_CORPUS);
+
+    assert!(!nested::WordRegex::new().is_ascii());
+}
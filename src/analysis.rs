@@ -0,0 +1,623 @@
+use crate::ehir::{Ehir, Token, Decision, Modifier, AnchorLocation, AnchorType, WordBoundaryType, Range};
+use crate::error::Span;
+use std::fmt;
+
+//A greedy repetition that can consume characters needed by what follows it. Native regexes never give
+//characters back, so the regex may fail to match where a backtracking regex would
+#[derive(Debug, Clone, PartialEq)]
+pub struct PossessiveWarning {
+    pub repetition: String, //The offending repetition, written as a regex
+    pub following: String, //The sub-pattern after the repetition that can match the same characters
+    pub repetition_span: Span, //Where the repetition is in the regex
+    pub following_span: Span, //Where the following sub-pattern is in the regex
+}
+
+impl PossessiveWarning {
+
+    //The part of the regex covering both the repetition and the sub-pattern that follows it
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.repetition_span.start.min(self.following_span.start),
+            end: self.repetition_span.end.max(self.following_span.end),
+        }
+    }
+}
+
+impl fmt::Display for PossessiveWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The greedy repetition '{}' can consume characters needed by '{}' which follows it. Native regexes do not backtrack, so this may not match where other regex engines would", self.repetition, self.following)
+    }
+}
+
+//A sub-pattern that can begin a match, along with the characters it accepts
+struct First {
+    ranges: Vec<(u32, u32)>,
+    text: String,
+    span: Span,
+}
+
+//The spans of the literals, classes and repetitions in the regex, which the tokens are traced back to by counting them
+struct Spans<'a> {
+    spans: & 'a [Span],
+    regex: Span,
+}
+
+impl<'a> Spans<'a> {
+
+    fn of(ehir: & 'a Ehir) -> Self {
+        Spans { spans: & ehir._spans, regex: Span { start: 0, end: ehir._regex.len() } }
+    }
+
+    //The span from the literal, class or repetition at `index` to the end of the `count`th one from it
+    fn span(&self, index: usize, count: usize) -> Span {
+        match (self.spans.get(index), self.spans.get(index + count.max(1) - 1)) {
+            (Some(start), Some(end)) => Span { start: start.start, end: end.end },
+            _ => self.regex
+        }
+    }
+}
+
+//Decompose a repetition block created by `Ehir::bounded_to_snippet` or `Ehir::unbounded_to_snippet` into its body and bounds
+fn repetition(token_list: & [Token]) -> Option<(& [Token], u32, Option<u32>)> {
+    match token_list {
+        [Token::StartCount, Token::While(_, block), Token::If(Modifier::Is, Decision::CountLessThan(n), _)] => {
+            match block.as_ref() {
+                Token::Block(inner) => match inner.as_slice() {
+                    [Token::Checkpoint(body, _), Token::IncrementCount] => Some((body, *n, None)),
+                    [Token::Checkpoint(body, _), Token::IncrementCount, Token::If(_, Decision::CountEquals(m), _)] => Some((body, *n, Some(*m))),
+                    _ => None
+                },
+                _ => None
+            }
+        }
+        _ => None
+    }
+}
+
+//The number of literals, classes and repetitions in the regex that the tokens were translated from
+fn atom_count(token_list: & [Token]) -> usize {
+    if let Some((body, _, _)) = repetition(token_list) {
+        return 1 + atom_count(body);
+    }
+
+    token_list.iter().map(|token| match token {
+        Token::If(Modifier::Not, Decision::Literal(_), _) | Token::If(Modifier::Not, Decision::CharacterSet(_), _) => 1,
+        Token::If(Modifier::Not, Decision::LiteralString(literal), _) => literal.chars().count(),
+        Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => atom_count(inner),
+        Token::Alternation(branches, _) => branches.iter().map(|branch| atom_count(branch)).sum(),
+        Token::Lazy(iteration, continuation, _, _, _) => 1 + atom_count(iteration) + atom_count(continuation),
+        _ => 0
+    }).sum()
+}
+
+fn ranges(decision: & Decision) -> Vec<(u32, u32)> {
+    match decision {
+        Decision::Literal(character) => vec![(*character, *character)],
+        Decision::LiteralString(literal) => literal.chars().map(|character| (character as u32, character as u32)).collect(),
+        Decision::CharacterSet(range_list) => range_list.iter().map(|range| match range {
+            Range::Single(n) => (*n, *n),
+            Range::Multiple(n, m) => (*n, *m),
+        }).collect(),
+        _ => vec![]
+    }
+}
+
+fn overlaps(a: & [(u32, u32)], b: & [(u32, u32)]) -> bool {
+    a.iter().any(|(a_start, a_end)| b.iter().any(|(b_start, b_end)| a_start <= b_end && b_start <= a_end))
+}
+
+fn escape_character(character: u32) -> String {
+    match std::char::from_u32(character) {
+        Some(ch) if !ch.is_control() => regex_syntax::escape(ch.to_string().as_str()),
+        _ => format!("\\x{{{:x}}}", character)
+    }
+}
+
+//Characters within a class only need escaping if they are special to the class
+fn escape_class_character(character: u32) -> String {
+    match std::char::from_u32(character) {
+        Some(ch) if "[]\\-^&~".contains(ch) => format!("\\{}", ch),
+        Some(ch) if !ch.is_control() => ch.to_string(),
+        _ => format!("\\x{{{:x}}}", character)
+    }
+}
+
+fn render_decision(decision: & Decision) -> String {
+    match decision {
+        Decision::Literal(character) => escape_character(*character),
+        Decision::LiteralString(literal) => regex_syntax::escape(literal),
+        Decision::CharacterSet(_) => {
+            let ranges = ranges(decision);
+
+            if ranges == [(0, 9), (11, 0x10FFFF)] {
+                return String::from(".");
+            }
+
+            if ranges == [(0, 0x10FFFF)] {
+                return String::from("(?s:.)");
+            }
+
+            if ranges.is_empty() {
+                return String::from("[^\\x{0}-\\x{10ffff}]");
+            }
+
+            let mut text = String::from("[");
+
+            for (start, end) in ranges {
+                if start == end {
+                    text.push_str(escape_class_character(start).as_str());
+                } else {
+                    text.push_str(format!("{}-{}", escape_class_character(start), escape_class_character(end)).as_str());
+                }
+            }
+
+            text.push(']');
+            text
+        }
+        _ => String::new()
+    }
+}
+
+fn render_quantifier(n: u32, m: Option<u32>) -> String {
+    match (n, m) {
+        (0, None) => String::from("*"),
+        (1, None) => String::from("+"),
+        (0, Some(1)) => String::from("?"),
+        (n, None) => format!("{{{},}}", n),
+        (n, Some(m)) if n == m => format!("{{{}}}", n),
+        (n, Some(m)) => format!("{{{},{}}}", n, m),
+    }
+}
+
+//Join the atoms of a repetition body, grouping them if there is more than one
+fn render_repeated(body: & [Token], quantifier: String) -> String {
+    let atoms = render_atoms(body);
+
+    if atoms.len() == 1 {
+        format!("{}{}", atoms[0], quantifier)
+    } else {
+        format!("(?:{}){}", atoms.concat(), quantifier)
+    }
+}
+
+//Render tokens back into regex syntax, one string per atom
+fn render_atoms(token_list: & [Token]) -> Vec<String> {
+    if let Some((body, n, m)) = repetition(token_list) {
+        return vec![render_repeated(body, render_quantifier(n, m))];
+    }
+
+    let mut atoms = vec![];
+
+    for token in token_list {
+        match token {
+            Token::If(Modifier::Not, decision @ Decision::Literal(_), _) | Token::If(Modifier::Not, decision @ Decision::CharacterSet(_), _) => {
+                atoms.push(render_decision(decision));
+            }
+            Token::If(Modifier::Not, Decision::LiteralString(literal), _) => {
+                atoms.extend(literal.chars().map(|character| escape_character(character as u32)));
+            }
+            Token::If(Modifier::Not, Decision::Anchor(anchor_type, anchor_location), _) => {
+                atoms.push(String::from(match (anchor_type, anchor_location) {
+                    (AnchorType::Regular, AnchorLocation::Start) => "\\A",
+                    (AnchorType::Regular, AnchorLocation::End) => "\\z",
+                    (AnchorType::Newline, AnchorLocation::Start) => "(?m:^)",
+                    (AnchorType::Newline, AnchorLocation::End) => "(?m:$)",
+                }));
+            }
+            Token::If(modifier, Decision::WordBoundary(boundary_type), _) => {
+                atoms.push(String::from(match (modifier, boundary_type) {
+                    (Modifier::Not, WordBoundaryType::Character) => "\\b",
+                    (Modifier::Is, WordBoundaryType::Character) => "\\B",
+                    (Modifier::Not, WordBoundaryType::Byte) => "(?-u:\\b)",
+                    (Modifier::Is, WordBoundaryType::Byte) => "(?-u:\\B)",
+                }));
+            }
+            Token::Capture(_, inner) => {
+                atoms.push(format!("({})", render_atoms(inner).concat()));
+            }
+            Token::Block(inner) | Token::Checkpoint(inner, _) => {
+                atoms.append(& mut render_atoms(inner));
+            }
+            Token::Alternation(branches, _) => {
+                let branches = branches.iter().map(|branch| render_atoms(branch).concat()).collect::<Vec<_>>();
+
+                atoms.push(format!("(?:{})", branches.join("|")));
+            }
+            Token::Lazy(iteration, continuation, n, m, _) => {
+                atoms.push(render_repeated(iteration, format!("{}?", render_quantifier(*n, *m))));
+                atoms.append(& mut render_atoms(continuation));
+            }
+            _ => {}
+        }
+    }
+
+    atoms
+}
+
+//The sub-patterns that can consume the first character matched by the tokens, and whether the tokens can match without consuming anything.
+//`index` counts the literals, classes and repetitions in the regex before the tokens
+fn first(token_list: & [Token], spans: & Spans, index: usize) -> (Vec<First>, bool) {
+    let mut firsts = vec![];
+
+    if let Some((body, n, _)) = repetition(token_list) {
+        let (body_firsts, nullable) = first(body, spans, index + 1);
+
+        //The first set of a repetition is that of its body, but named after the whole repetition
+        let text = render_atoms(token_list).concat();
+
+        for body_first in body_firsts {
+            firsts.push(First { ranges: body_first.ranges, text: text.clone(), span: spans.span(index, 1) });
+        }
+
+        return (firsts, nullable || n == 0);
+    }
+
+    let mut index = index;
+
+    for token in token_list {
+        let (mut token_firsts, nullable) = match token {
+            Token::If(Modifier::Not, decision @ Decision::Literal(_), _) | Token::If(Modifier::Not, decision @ Decision::CharacterSet(_), _) => {
+                (vec![First { ranges: ranges(decision), text: render_decision(decision), span: spans.span(index, 1) }], false)
+            }
+            Token::If(Modifier::Not, Decision::LiteralString(literal), _) => {
+                let character = literal.chars().next().unwrap() as u32;
+                let span = spans.span(index, literal.chars().count());
+
+                (vec![First { ranges: vec![(character, character)], text: render_decision(& Decision::LiteralString(literal.clone())), span }], false)
+            }
+            Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => {
+                first(inner, spans, index)
+            }
+            Token::Alternation(branches, _) => {
+                let mut branch_firsts = vec![];
+                let mut any_nullable = false;
+                let mut branch_index = index;
+
+                for branch in branches {
+                    let (mut firsts, nullable) = first(branch, spans, branch_index);
+                    branch_firsts.append(& mut firsts);
+                    any_nullable |= nullable;
+                    branch_index += atom_count(branch);
+                }
+
+                (branch_firsts, any_nullable)
+            }
+            Token::Lazy(iteration, continuation, n, _, _) => {
+                let (mut lazy_firsts, nullable) = first(iteration, spans, index + 1);
+
+                if nullable || *n == 0 {
+                    let (mut continuation_firsts, continuation_nullable) = first(continuation, spans, index + 1 + atom_count(iteration));
+                    lazy_firsts.append(& mut continuation_firsts);
+                    (lazy_firsts, continuation_nullable)
+                } else {
+                    (lazy_firsts, false)
+                }
+            }
+            _ => (vec![], true)
+        };
+
+        firsts.append(& mut token_firsts);
+        index += atom_count(std::slice::from_ref(token));
+
+        if !nullable {
+            return (firsts, false);
+        }
+    }
+
+    (firsts, true)
+}
+
+//Every character that the tokens could consume
+fn consumable(token_list: & [Token], consumed: & mut Vec<(u32, u32)>) {
+    for token in token_list {
+        match token {
+            Token::If(Modifier::Not, decision, _) => {
+                consumed.append(& mut ranges(decision));
+            }
+            Token::While(_, inner) => {
+                consumable(std::slice::from_ref(inner.as_ref()), consumed);
+            }
+            Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => {
+                consumable(inner, consumed);
+            }
+            Token::Alternation(branches, _) => {
+                for branch in branches {
+                    consumable(branch, consumed);
+                }
+            }
+            Token::Lazy(iteration, continuation, _, _, _) => {
+                consumable(iteration, consumed);
+                consumable(continuation, consumed);
+            }
+            _ => {}
+        }
+    }
+}
+
+//Walk the tokens looking for greedy repetitions, where `outer` is what can follow the whole list and `index` counts the literals, classes and repetitions before it
+fn walk(token_list: & [Token], outer: & [& First], spans: & Spans, index: usize, warnings: & mut Vec<PossessiveWarning>) {
+
+    if let Some((body, _, _)) = repetition(token_list) {
+        let mut consumed = vec![];
+        consumable(body, & mut consumed);
+
+        if let Some(following) = outer.iter().find(|following| overlaps(& consumed, & following.ranges)) {
+            let warning = PossessiveWarning {
+                repetition: render_atoms(token_list).concat(),
+                following: following.text.clone(),
+                repetition_span: spans.span(index, 1),
+                following_span: following.span,
+            };
+
+            if !warnings.contains(& warning) {
+                warnings.push(warning);
+            }
+        }
+
+        //The end of an iteration can be followed by another iteration, or whatever follows the repetition
+        let (body_firsts, _) = first(body, spans, index + 1);
+        let follow = body_firsts.iter().chain(outer.iter().copied()).collect::<Vec<_>>();
+
+        walk(body, & follow, spans, index + 1, warnings);
+
+        return;
+    }
+
+    let mut index = index;
+
+    for (i, token) in token_list.iter().enumerate() {
+        let count = atom_count(std::slice::from_ref(token));
+        let (rest_firsts, rest_nullable) = first(& token_list[i + 1..], spans, index + count);
+
+        let mut follow = rest_firsts.iter().collect::<Vec<_>>();
+
+        if rest_nullable {
+            follow.extend(outer.iter().copied());
+        }
+
+        match token {
+            Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => {
+                walk(inner, & follow, spans, index, warnings);
+            }
+            Token::Alternation(branches, _) => {
+                let mut branch_index = index;
+
+                for branch in branches {
+                    walk(branch, & follow, spans, branch_index, warnings);
+                    branch_index += atom_count(branch);
+                }
+            }
+            Token::Lazy(iteration, continuation, _, _, _) => {
+                let continuation_index = index + 1 + atom_count(iteration);
+
+                let (mut lazy_follow, _) = first(continuation, spans, continuation_index);
+                lazy_follow.append(& mut first(iteration, spans, index + 1).0);
+
+                let mut iteration_follow = lazy_follow.iter().collect::<Vec<_>>();
+                iteration_follow.extend(follow.iter().copied());
+
+                walk(iteration, & iteration_follow, spans, index + 1, warnings);
+                walk(continuation, & follow, spans, continuation_index, warnings);
+            }
+            _ => {}
+        }
+
+        index += count;
+    }
+}
+
+//Find greedy repetitions whose possessive matching may differ from a backtracking regex engine, such as `[0-9]*[0-9]` or `.*foo`
+pub fn possessive_warnings(ehir: & Ehir) -> Vec<PossessiveWarning> {
+    let mut warnings = vec![];
+
+    walk(& ehir._tokens, & [], & Spans::of(ehir), 0, & mut warnings);
+
+    warnings
+}
+
+//Append the literals at the start of the tokens to `prefix`, returning false at the first token that is not a literal
+fn prefix(token_list: & [Token], literal: & mut String) -> bool {
+    for token in token_list {
+        match token {
+            Token::If(Modifier::Not, Decision::Middle, _) | Token::Advance | Token::AdvanceBy(_) | Token::Empty => {}
+            Token::If(Modifier::Not, Decision::Literal(character), _) => match std::char::from_u32(*character) {
+                Some(character) => literal.push(character),
+                None => return false
+            },
+            Token::If(Modifier::Not, Decision::LiteralString(string), _) => {
+                literal.push_str(string);
+            }
+            //Anchors and word boundaries consume nothing, so the literals either side of them are still adjacent
+            Token::If(_, Decision::Anchor(_, _), _) | Token::If(_, Decision::WordBoundary(_), _) => {}
+            Token::Capture(_, inner) | Token::Block(inner) => {
+                if !prefix(inner, literal) {
+                    return false;
+                }
+            }
+            _ => return false
+        }
+    }
+
+    true
+}
+
+//The literal text every match starts with, such as "ERROR: " for `ERROR: (.*)`. Empty if matches can start with anything else
+pub fn literal_prefix(ehir: & Ehir) -> String {
+    let mut literal = String::new();
+
+    prefix(& ehir._tokens, & mut literal);
+
+    literal
+}
+
+//The characters a match can start with, as sorted and merged ranges. None if a match can be empty, or can start with any character
+pub fn first_characters(ehir: & Ehir) -> Option<Vec<(u32, u32)>> {
+    let (firsts, nullable) = first(& ehir._tokens, & Spans::of(ehir), 0);
+
+    if nullable {
+        return None;
+    }
+
+    let mut ranges = firsts.into_iter().flat_map(|first| first.ranges).collect::<Vec<_>>();
+
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u32, u32)> = vec![];
+
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end))
+        }
+    }
+
+    if merged == [(0, 0x10FFFF)] {
+        return None;
+    }
+
+    Some(merged)
+}
+
+//The fewest and most bytes a character matching the decision can take up
+fn character_length(decision: & Decision) -> (usize, usize) {
+    let length = |character: u32| std::char::from_u32(character).map_or(4, char::len_utf8);
+
+    match decision {
+        Decision::Literal(character) => (length(*character), length(*character)),
+        Decision::LiteralString(literal) => (literal.len(), literal.len()),
+        Decision::CharacterSet(_) => {
+            let ranges = ranges(decision);
+
+            let shortest = ranges.iter().map(|(start, _)| length(*start)).min().unwrap_or(0);
+            let longest = ranges.iter().map(|(_, end)| length(*end)).max().unwrap_or(0);
+
+            (shortest, longest)
+        }
+        _ => (0, 0)
+    }
+}
+
+//The shortest and longest (None if unbounded) match of the tokens, in bytes
+fn length(token_list: & [Token]) -> (usize, Option<usize>) {
+    if let Some((body, n, m)) = repetition(token_list) {
+        let (shortest, longest) = length(body);
+
+        let longest = match (longest, m) {
+            (Some(0), _) => Some(0),
+            (Some(longest), Some(m)) => longest.checked_mul(m as usize),
+            _ => None
+        };
+
+        return (shortest.saturating_mul(n as usize), longest);
+    }
+
+    let mut total = (0usize, Some(0usize));
+
+    for token in token_list {
+        let (shortest, longest) = match token {
+            Token::If(Modifier::Not, decision, _) => {
+                let (shortest, longest) = character_length(decision);
+
+                (shortest, Some(longest))
+            }
+            Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => length(inner),
+            Token::Alternation(branches, _) => {
+                let lengths = branches.iter().map(|branch| length(branch)).collect::<Vec<_>>();
+
+                let shortest = lengths.iter().map(|(shortest, _)| *shortest).min().unwrap_or(0);
+                let longest = lengths.iter().map(|(_, longest)| *longest).max().unwrap_or(Some(0));
+
+                //`max` puts None first, but here None is the longest
+                if lengths.iter().any(|(_, longest)| longest.is_none()) {
+                    (shortest, None)
+                } else {
+                    (shortest, longest)
+                }
+            }
+            Token::Lazy(iteration, continuation, n, m, _) => {
+                let (iteration_shortest, iteration_longest) = length(iteration);
+                let (continuation_shortest, continuation_longest) = length(continuation);
+
+                let longest = match (iteration_longest, m) {
+                    (Some(0), _) => Some(0),
+                    (Some(longest), Some(m)) => longest.checked_mul(*m as usize),
+                    _ => None
+                };
+
+                (iteration_shortest.saturating_mul(*n as usize).saturating_add(continuation_shortest), longest.zip(continuation_longest).and_then(|(a, b)| a.checked_add(b)))
+            }
+            //Loops outside of a repetition block could match anything
+            Token::While(_, _) => (0, None),
+            _ => (0, Some(0))
+        };
+
+        total = (total.0.saturating_add(shortest), total.1.zip(longest).and_then(|(a, b)| a.checked_add(b)));
+    }
+
+    total
+}
+
+//The shortest and longest (None if unbounded) text a match can span, in bytes
+pub fn match_length(ehir: & Ehir) -> (usize, Option<usize>) {
+    length(& ehir._tokens)
+}
+
+//The start anchor that every match of the tokens begins with, if any
+fn anchor(token_list: & [Token]) -> Option<AnchorType> {
+    for token in token_list {
+        match token {
+            Token::If(Modifier::Not, Decision::Middle, _) => {}
+            Token::If(Modifier::Not, Decision::Anchor(anchor_type, AnchorLocation::Start), _) => return Some(*anchor_type),
+            Token::Capture(_, inner) | Token::Block(inner) => return anchor(inner),
+            Token::Alternation(branches, _) => {
+                let anchors = branches.iter().map(|branch| anchor(branch)).collect::<Option<Vec<_>>>()?;
+
+                //A branch anchored to the start of a line is the weaker anchor
+                return Some(if anchors.iter().all(|anchor_type| *anchor_type == AnchorType::Regular) {
+                    AnchorType::Regular
+                } else {
+                    AnchorType::Newline
+                });
+            }
+            _ => return None
+        }
+    }
+
+    None
+}
+
+//Whether every match starts at the start of the text (`Regular`, such as `^` or `\A`) or of a line (`Newline`, such as `(?m)^`)
+pub fn start_anchor(ehir: & Ehir) -> Option<AnchorType> {
+    anchor(& ehir._tokens)
+}
+
+//Whether the decision can only hold for ASCII characters, or does not look at characters beyond ASCII
+fn ascii_decision(decision: & Decision) -> bool {
+    match decision {
+        Decision::CharacterSet(range_list) => range_list.iter().all(|range| match range {
+            Range::Single(n) => *n < 128,
+            Range::Multiple(_, m) => *m < 128,
+        }),
+        Decision::Literal(character) => *character < 128,
+        Decision::LiteralString(literal) => literal.is_ascii(),
+        Decision::WordBoundary(WordBoundaryType::Character) => false,
+        _ => true
+    }
+}
+
+fn ascii(token_list: & [Token]) -> bool {
+    token_list.iter().all(|token| match token {
+        Token::If(_, decision, _) => ascii_decision(decision),
+        Token::While(decision, token) => ascii_decision(decision) && ascii(std::slice::from_ref(token.as_ref())),
+        Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => ascii(inner),
+        Token::Alternation(branches, _) => branches.iter().all(|branch| ascii(branch)),
+        Token::Lazy(iteration, continuation, _, _, _) => ascii(iteration) && ascii(continuation),
+        _ => true
+    })
+}
+
+//Whether the regex only ever consumes ASCII characters and only looks at ASCII ones, so it can be matched over bytes.
+//Classes that include anything above ASCII, such as `[^a]` or Unicode `\w`, and Unicode word boundaries rule this out
+pub fn is_ascii(ehir: & Ehir) -> bool {
+    ascii(& ehir._tokens)
+}
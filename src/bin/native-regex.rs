@@ -0,0 +1,174 @@
+use native_regex_lib::ehir::Ehir;
+use native_regex_lib::options::TranslateOptions;
+use native_regex_lib::rust_translate::translate_with_options;
+use std::io::{Read, Write};
+use std::process::exit;
+
+const USAGE: &str = "Translate regexes into Rust source code
+
+Usage: native-regex [OPTIONS] [PATTERN]...
+
+Each PATTERN argument is one regex. With no patterns, regexes are read one per line from --file, or from stdin.
+Put -- before any pattern that starts with '-'.
+
+Options:
+  -n, --name <NAME>                 Name of the generated struct [default: GeneratedRegex]
+  -f, --file <PATH>                 Read patterns from a file, one per line
+  -o, --output <PATH>               Write the generated code to a file instead of stdout
+      --check                       Only check that every pattern translates, printing any errors
+      --ehir                        Print the Ehir of every pattern instead of generating code
+      --backtracking                Generate a backtracking matcher
+      --deny-possessive-warnings    Fail on greedy repetitions that may match differently without backtracking
+  -i, --case-insensitive            Match case insensitively, as with (?i)
+      --multi-line                  Make ^ and $ match at line boundaries, as with (?m)
+      --dot-matches-new-line        Make . match \n, as with (?s)
+      --swap-greed                  Make repetitions lazy unless followed by ?, as with (?U)
+  -x, --ignore-whitespace           Ignore whitespace and # comments in patterns, as with (?x)
+      --no-unicode                  Use ASCII classes and word boundaries, as with (?-u)
+      --range-table-threshold <N>   Look up character classes with more than N ranges in a table [default: 32]
+  -h, --help                        Print this message";
+
+//What the command line asks for
+struct Arguments {
+    patterns: Vec<String>,
+    name: String,
+    file: Option<String>,
+    output: Option<String>,
+    check: bool,
+    ehir: bool,
+    options: TranslateOptions,
+}
+
+//Print a usage error and exit
+fn usage_error(message: & str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    exit(2)
+}
+
+//The value following a flag
+fn value(args: & mut impl Iterator<Item = String>, flag: & str) -> String {
+    args.next().unwrap_or_else(|| usage_error(format!("{} needs a value", flag).as_str()))
+}
+
+fn parse_arguments() -> Arguments {
+    let mut arguments = Arguments {
+        patterns: vec![],
+        name: String::from("GeneratedRegex"),
+        file: None,
+        output: None,
+        check: false,
+        ehir: false,
+        options: TranslateOptions::default(),
+    };
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--name" => arguments.name = value(& mut args, arg.as_str()),
+            "-f" | "--file" => arguments.file = Some(value(& mut args, arg.as_str())),
+            "-o" | "--output" => arguments.output = Some(value(& mut args, arg.as_str())),
+            "--check" => arguments.check = true,
+            "--ehir" => arguments.ehir = true,
+            "--backtracking" => arguments.options.backtracking = true,
+            "--deny-possessive-warnings" => arguments.options.deny_possessive_warnings = true,
+            "-i" | "--case-insensitive" => arguments.options.case_insensitive = true,
+            "--multi-line" => arguments.options.multi_line = true,
+            "--dot-matches-new-line" => arguments.options.dot_matches_new_line = true,
+            "--swap-greed" => arguments.options.swap_greed = true,
+            "-x" | "--ignore-whitespace" => arguments.options.ignore_whitespace = true,
+            "--no-unicode" => arguments.options.unicode = false,
+            "--range-table-threshold" => {
+                let threshold = value(& mut args, arg.as_str());
+
+                arguments.options.range_table_threshold = threshold.parse().unwrap_or_else(|_| usage_error(format!("{} needs a number, not '{}'", arg, threshold).as_str()));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                exit(0)
+            }
+            "--" => arguments.patterns.extend(args.by_ref()),
+            flag if flag.starts_with('-') && flag.len() > 1 => usage_error(format!("unknown option '{}'", flag).as_str()),
+            _ => arguments.patterns.push(arg),
+        }
+    }
+
+    if !arguments.patterns.is_empty() && arguments.file.is_some() {
+        usage_error("patterns cannot be given as arguments and with --file");
+    }
+
+    arguments
+}
+
+//Patterns given as arguments, or else one per line from the file or stdin
+fn read_patterns(arguments: & Arguments) -> Result<Vec<String>, String> {
+    if !arguments.patterns.is_empty() {
+        return Ok(arguments.patterns.clone());
+    }
+
+    let text = match & arguments.file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(& mut text).map_err(|e| format!("stdin: {}", e))?;
+            text
+        }
+    };
+
+    Ok(text.lines().map(|line| line.trim_end_matches('\r')).filter(|line| !line.is_empty()).map(String::from).collect())
+}
+
+fn write_output(arguments: & Arguments, text: & str) -> Result<(), String> {
+    match & arguments.output {
+        Some(path) => std::fs::write(path, text).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(text.as_bytes()).map_err(|e| format!("stdout: {}", e)),
+    }
+}
+
+fn run(arguments: & Arguments) -> Result<(), String> {
+    let patterns = read_patterns(arguments)?;
+
+    if patterns.is_empty() {
+        usage_error("no patterns given");
+    }
+
+    if arguments.check {
+        let mut failed = false;
+
+        for pattern in patterns.iter() {
+            if let Err(error) = translate_with_options(pattern, arguments.name.as_str(), & arguments.options) {
+                eprintln!("{}", error);
+                failed = true;
+            }
+        }
+
+        return if failed { Err(String::from("some patterns could not be translated")) } else { Ok(()) };
+    }
+
+    if arguments.ehir {
+        let mut text = String::new();
+
+        for pattern in patterns.iter() {
+            text.push_str(format!("{:#?}\n", Ehir::translate_with_options(pattern, & arguments.options).map_err(|e| e.to_string())?).as_str());
+        }
+
+        return write_output(arguments, text.as_str());
+    }
+
+    if patterns.len() != 1 {
+        usage_error("code is generated for exactly one pattern at a time");
+    }
+
+    let code = translate_with_options(patterns[0].as_str(), arguments.name.as_str(), & arguments.options).map_err(|e| e.to_string())?;
+
+    write_output(arguments, format!("{}\n", code).as_str())
+}
+
+fn main() {
+    let arguments = parse_arguments();
+
+    if let Err(message) = run(& arguments) {
+        eprintln!("error: {}", message);
+        exit(1)
+    }
+}
@@ -0,0 +1,142 @@
+use crate::options::TranslateOptions;
+use crate::error::TranslateError;
+use crate::rust_translate::translate_with_options;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{Document, Item, TableLike};
+
+//Convert a byte offset into the manifest into a 1-based line and column
+fn line_column(manifest: & str, offset: usize) -> (usize, usize) {
+    let before = &manifest[..offset.min(manifest.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap().chars().count() + 1;
+
+    (line, column)
+}
+
+//Prefix a message with the location of `span` in the manifest
+fn located(path: & Path, manifest: & str, span: Option<Range<usize>>, message: & str) -> String {
+    let (line, column) = line_column(manifest, span.map(|span| span.start).unwrap_or(0));
+
+    format!("{}:{}:{}: {}", path.display(), line, column, message)
+}
+
+//Where an error in the pattern lies in the manifest. Single line strings without escapes hold the pattern as written,
+//so the error can be pointed at exactly, otherwise the start of the pattern is used
+fn error_span(manifest: & str, pattern: & Item, error: & TranslateError) -> Option<Range<usize>> {
+    let span = pattern.span()?;
+    let raw = manifest.get(span.clone())?;
+
+    if raw.starts_with("'''") || raw.starts_with("\"\"\"") || (raw.starts_with('"') && raw.contains('\\')) {
+        Some(span)
+    } else {
+        let start = span.start + 1 + error.span().start;
+        Some(start..start)
+    }
+}
+
+fn is_identifier(name: & str) -> bool {
+    let mut characters = name.chars();
+
+    match characters.next() {
+        Some(first) => (first.is_ascii_alphabetic() || first == '_') && characters.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        None => false,
+    }
+}
+
+//Read the pattern and options of a single manifest entry
+fn entry_to_options<'a>(path: & Path, manifest: & str, name: & str, name_span: Option<Range<usize>>, entry: & 'a dyn TableLike) -> Result<(& 'a Item, TranslateOptions), String> {
+    let mut pattern = None;
+    let mut options = TranslateOptions::default();
+
+    for (key, item) in entry.iter() {
+        let flag = match key {
+            "pattern" => {
+                pattern = Some(item);
+                continue;
+            }
+            "range_table_threshold" => {
+                let threshold = item.as_integer().filter(|threshold| *threshold >= 0);
+
+                options.range_table_threshold = threshold.ok_or_else(|| located(path, manifest, item.span(), format!("'{}' must be a non-negative integer", key).as_str()))? as usize;
+                continue;
+            }
+            "backtracking" => & mut options.backtracking,
+            "deny_possessive_warnings" => & mut options.deny_possessive_warnings,
+            "case_insensitive" => & mut options.case_insensitive,
+            "multi_line" => & mut options.multi_line,
+            "dot_matches_new_line" => & mut options.dot_matches_new_line,
+            "swap_greed" => & mut options.swap_greed,
+            "ignore_whitespace" => & mut options.ignore_whitespace,
+            "unicode" => & mut options.unicode,
+            _ => return Err(located(path, manifest, entry.key(key).and_then(|key| key.span()), format!("unknown key '{}'", key).as_str())),
+        };
+
+        *flag = item.as_bool().ok_or_else(|| located(path, manifest, item.span(), format!("'{}' must be a boolean", key).as_str()))?;
+    }
+
+    match pattern {
+        Some(pattern) => Ok((pattern, options)),
+        None => Err(located(path, manifest, name_span, format!("missing 'pattern' for '{}'", name).as_str())),
+    }
+}
+
+//Translate every entry of a manifest into the source of one module
+fn manifest_to_module(path: & Path, manifest: & str) -> Result<String, String> {
+    let document = Document::parse(manifest).map_err(|e| located(path, manifest, e.span(), e.message()))?;
+
+    let mut code = String::new();
+
+    code.push_str(format!("// Generated by native_regex_lib::build from '{}', do not edit\n", path.display()).as_str());
+
+    for (name, item) in document.iter() {
+        let name_span = document.key(name).and_then(|key| key.span());
+
+        if !is_identifier(name) {
+            return Err(located(path, manifest, name_span, format!("'{}' is not a valid struct name", name).as_str()));
+        }
+
+        let entry = item.as_table_like().ok_or_else(|| located(path, manifest, name_span.clone(), format!("'{}' must be a table", name).as_str()))?;
+
+        let (pattern, options) = entry_to_options(path, manifest, name, name_span, entry)?;
+
+        let regex = pattern.as_str().ok_or_else(|| located(path, manifest, pattern.span(), "'pattern' must be a string"))?;
+
+        let translated = translate_with_options(regex, name, & options).map_err(|error| located(path, manifest, error_span(manifest, pattern, & error), error.to_string().as_str()))?;
+
+        code.push('\n');
+        code.push_str(translated.as_str());
+        code.push('\n');
+    }
+
+    Ok(code)
+}
+
+//Generate a module of NativeRegex structs from a TOML manifest, for use in a build script. Each table in the
+//manifest names a struct, and contains its `pattern` along with any `TranslateOptions` fields, by their field names
+//
+//    [IpRegex]
+//    pattern = '([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})'
+//    backtracking = false
+//    case_insensitive = true
+//    range_table_threshold = 16
+//
+//The module is written to `out_dir` with the same name as the manifest (`regexes.toml` becomes `regexes.rs`) and its
+//path is returned, ready for `include!`. Errors are prefixed with the manifest path, line and column of the problem
+pub fn generate<P: AsRef<Path>, Q: AsRef<Path>>(manifest_path: P, out_dir: Q) -> Result<PathBuf, String> {
+    let manifest_path = manifest_path.as_ref();
+
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+    let manifest = std::fs::read_to_string(manifest_path).map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+
+    let code = manifest_to_module(manifest_path, manifest.as_str())?;
+
+    let stem = manifest_path.file_stem().ok_or_else(|| format!("{}: manifest has no file name", manifest_path.display()))?;
+    let module_path = out_dir.as_ref().join(stem).with_extension("rs");
+
+    std::fs::write(& module_path, code).map_err(|e| format!("{}: {}", module_path.display(), e))?;
+
+    Ok(module_path)
+}
@@ -0,0 +1,107 @@
+use crate::native_regex::NativeRegex;
+use crate::interpreter::EhirInterpreter;
+use regex::Regex;
+use std::fmt;
+
+//The result of one operation, as spans into the text
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Find(Option<(usize, usize)>),
+    Captures(Option<Vec<Option<(usize, usize)>>>),
+    FindIter(Vec<(usize, usize)>),
+}
+
+//An input where the native regex and `regex::Regex` disagree
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub input: String,
+    pub expected: Outcome, //The result from `regex::Regex`
+    pub actual: Outcome, //The result from the native regex
+    pub shrunk: String, //The smallest input found that still diverges in the same operation
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Input {:?} expected {:?} but got {:?} (shrunk to {:?})", self.input, self.expected, self.actual, self.shrunk)
+    }
+}
+
+fn native_outcomes<R: NativeRegex>(native: & R, text: & str) -> [Outcome; 3] {
+    [
+        Outcome::Find(native.find(text).map(|m| (m.start(), m.end()))),
+        Outcome::Captures(native.captures(text).map(|captures| captures.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect())),
+        Outcome::FindIter(native.find_iter(text).map(|m| (m.start(), m.end())).collect()),
+    ]
+}
+
+fn regex_outcomes(regex: & Regex, text: & str) -> [Outcome; 3] {
+    [
+        Outcome::Find(regex.find(text).map(|m| (m.start(), m.end()))),
+        Outcome::Captures(regex.captures(text).map(|captures| captures.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect())),
+        Outcome::FindIter(regex.find_iter(text).map(|m| (m.start(), m.end())).collect()),
+    ]
+}
+
+//Remove as much of the input as possible while the given operation still diverges
+fn shrink<R: NativeRegex>(native: & R, regex: & Regex, input: & str, operation: usize) -> String {
+    let diverges = |text: & str| native_outcomes(native, text)[operation] != regex_outcomes(regex, text)[operation];
+
+    let mut current = input.chars().collect::<Vec<_>>();
+    let mut chunk = (current.len() / 2).max(1);
+
+    while !current.is_empty() {
+        let mut removed = false;
+        let mut i = 0;
+
+        while i + chunk <= current.len() {
+            let candidate = current[..i].iter().chain(current[i + chunk..].iter()).collect::<String>();
+
+            if diverges(candidate.as_str()) {
+                current = candidate.chars().collect();
+                removed = true;
+            } else {
+                i += chunk;
+            }
+        }
+
+        if !removed {
+            if chunk == 1 {
+                break;
+            }
+            chunk /= 2;
+        }
+    }
+
+    current.into_iter().collect()
+}
+
+//Run `find`, `captures` and `find_iter` over every input in the corpus, reporting each disagreement with `regex`
+pub fn compare<R: NativeRegex>(native: & R, regex: & Regex, corpus: & [& str]) -> Vec<Divergence> {
+    let mut divergences = vec![];
+
+    for input in corpus {
+        let actual = native_outcomes(native, input);
+        let expected = regex_outcomes(regex, input);
+
+        for (operation, (actual, expected)) in actual.iter().zip(expected.iter()).enumerate() {
+            if actual != expected {
+                divergences.push(Divergence {
+                    input: input.to_string(),
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                    shrunk: shrink(native, regex, input, operation),
+                });
+            }
+        }
+    }
+
+    divergences
+}
+
+//Compare the Ehir interpreter for `pattern` against `regex::Regex` over the corpus
+pub fn compare_pattern(pattern: & str, corpus: & [& str]) -> Result<Vec<Divergence>, String> {
+    let native = EhirInterpreter::new(pattern).map_err(|e| e.to_string())?;
+    let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+
+    Ok(compare(& native, & regex, corpus))
+}
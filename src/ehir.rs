@@ -1,9 +1,12 @@
 
 use std::collections::HashMap;
 use regex_syntax::hir::*;
-use regex_syntax::Parser;
+use regex_syntax::ParserBuilder;
+use regex_syntax::ast::{self, Ast};
+use crate::options::TranslateOptions;
+use crate::error::{Span, TranslateError};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NoMatch {
     Stop,
     Break,
@@ -21,7 +24,7 @@ pub enum AnchorLocation {
     End
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AnchorType {
     Regular,
     Newline
@@ -42,7 +45,9 @@ pub enum Decision {
     CountLessThan(u32),
     Anchor(AnchorType, AnchorLocation),
     WordBoundary(WordBoundaryType),
-    Middle //Determine if we are NOT at the end of the text
+    EmptyIteration(u32), //The iteration consumed nothing, and at least this many iterations matched before it
+    Middle, //Determine if we are NOT at the end of the text
+    Always, //Loop until the body breaks out, for repetitions that can match empty at the end of the text
 }
 
 #[derive(Debug)]
@@ -64,52 +69,187 @@ pub enum Token {
     StartCount, //Set the counter to zero. Used in repetition to check bounds
     IncrementCount, //Increment the counter every time a repetition matches
     Advance, //Advance to the next character
+    AdvanceBy(u32), //Advance past the given number of characters
     Capture(u32, Vec<Token>), //TOken representing a capturing group
     Block(Vec<Token>),
+    Alternation(Vec<Vec<Token>>, NoMatch), //Ordered choice, the first branch to match wins. Position and captures are restored between branches
+    Checkpoint(Vec<Token>, NoMatch), //Run the tokens, rewinding to the position and captures they started from if they fail
+    Lazy(Vec<Token>, Vec<Token>, u32, Option<u32>, NoMatch), //Lazy repetition of an iteration between the bounds. The continuation (second list) is tried before each extra iteration
+    IterationStart, //Remember where an iteration started, for `Decision::EmptyIteration`
     Empty,
 }
 
+impl Token {
+
+    //Collect the indices of all capture groups contained within the token
+    pub fn capture_indices(&self, indices: & mut Vec<u32>) {
+        match self {
+            Token::While(_, token) => {
+                token.capture_indices(indices);
+            }
+            Token::Capture(index, token_list) => {
+                indices.push(*index);
+
+                for token in token_list {
+                    token.capture_indices(indices);
+                }
+            }
+            Token::Block(token_list) => {
+                for token in token_list {
+                    token.capture_indices(indices);
+                }
+            }
+            Token::Checkpoint(token_list, _) => {
+                for token in token_list {
+                    token.capture_indices(indices);
+                }
+            }
+            Token::Lazy(iteration, continuation, _, _, _) => {
+                for token in iteration.iter().chain(continuation.iter()) {
+                    token.capture_indices(indices);
+                }
+            }
+            Token::Alternation(branches, _) => {
+                for token in branches.iter().flatten() {
+                    token.capture_indices(indices);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Ehir<'r> {
     pub _regex: & 'r str,
     pub _tokens: Vec<Token>, //List of tokens representing the EHIR
     pub _capture_names: HashMap<String, u32>, //A hashmap of all named capture groups and their corresponding indices
     pub _capture_count: u32, //Total number of capture groups, (including the entire match)
+    pub _spans: Vec<Span>, //Where each literal, class and repetition is in the regex, in the order they appear
 }
 
 impl<'r> Ehir<'r> {
 
-    fn bounds_check() -> Token {
-        Token::If(Modifier::Not, Decision::Middle, NoMatch::Stop)
+    fn bounds_check(in_inner_loop: bool) -> Token {
+        Token::If(Modifier::Not, Decision::Middle, Ehir::stop_break(in_inner_loop))
     }
 
     fn stop_break(is_inner_loop: bool) -> NoMatch {
         if is_inner_loop { NoMatch::Break } else { NoMatch::Stop }
     }
 
-    fn bounded_to_snippet(mut inner_code: Vec<Token>, in_inner_loop: bool, n: u32, m: u32) -> Vec<Token> {
+    //Each iteration is a checkpoint, so a failed iteration gives back anything it consumed or captured before ending the loop
+    fn iteration_snippet(inner_code: Vec<Token>) -> Vec<Token> {
+        vec![Token::Checkpoint(inner_code, NoMatch::Break)]
+    }
+
+    //An iteration of a sub-pattern that can match empty fails if it consumes nothing, once the minimum has been reached.
+    //The first iteration may always be empty, as in other regex engines, so `(a*)*` still captures an empty group
+    fn stop_when_empty(inner_code: Vec<Token>, n: u32) -> Vec<Token> {
+        let mut iteration = vec![Token::IterationStart];
+
+        iteration.extend(inner_code);
+        iteration.push(Token::If(Modifier::Is, Decision::EmptyIteration(n.max(1)), NoMatch::Break));
+
+        iteration
+    }
+
+    //Loop while there is text left, unless an iteration can match empty. Then the loop must also run at the end of the text,
+    //and `stop_when_empty` ends it
+    fn loop_decision(can_be_empty: bool) -> Decision {
+        if can_be_empty { Decision::Always } else { Decision::Middle }
+    }
+
+    fn bounded_to_snippet(inner_code: Vec<Token>, in_inner_loop: bool, can_be_empty: bool, n: u32, m: u32) -> Vec<Token> {
+
+        let mut inner_code = Ehir::iteration_snippet(inner_code);
 
         inner_code.push(Token::IncrementCount);
         inner_code.push(Token::If(Modifier::Is, Decision::CountEquals(m), NoMatch::Break));
 
         vec![Token::Block(vec![
             Token::StartCount,
-            Token::While(Decision::Middle, Box::new(Token::Block(inner_code))),
+            Token::While(Ehir::loop_decision(can_be_empty), Box::new(Token::Block(inner_code))),
             Token::If(Modifier::Is, Decision::CountLessThan(n), Ehir::stop_break(in_inner_loop))
         ])]
     }
 
-    fn unbounded_to_snippet(mut inner_code: Vec<Token>, in_inner_loop: bool, n: u32) -> Vec<Token> {
+    fn unbounded_to_snippet(inner_code: Vec<Token>, in_inner_loop: bool, can_be_empty: bool, n: u32) -> Vec<Token> {
+
+        let mut inner_code = Ehir::iteration_snippet(inner_code);
 
         inner_code.push(Token::IncrementCount);
 
         vec![Token::Block(vec![
             Token::StartCount,
-            Token::While(Decision::Middle, Box::new(Token::Block(inner_code))),
+            Token::While(Ehir::loop_decision(can_be_empty), Box::new(Token::Block(inner_code))),
             Token::If(Modifier::Is, Decision::CountLessThan(n), Ehir::stop_break(in_inner_loop))
         ])]
     }
 
+    fn lazy_to_snippet(inner_code: Vec<Token>, continuation: Vec<Token>, in_inner_loop: bool, n: u32, m: Option<u32>) -> Vec<Token> {
+        vec![Token::Lazy(Ehir::iteration_snippet(inner_code), continuation, n, m, Ehir::stop_break(in_inner_loop))]
+    }
+
+    //Convert a unicode or byte class into the list of ranges it contains
+    pub(crate) fn class_to_ranges(class: & Class) -> Vec<Range> {
+        let to_range = |start: u32, end: u32| {
+            if start == end {
+                Range::Single(start)
+            } else {
+                Range::Multiple(start, end)
+            }
+        };
+
+        match class {
+            Class::Unicode(unicode) => {
+                unicode.iter().map(|range| to_range(range.start() as u32, range.end() as u32)).collect()
+            }
+            Class::Bytes(bytes) => {
+                bytes.iter().map(|range| to_range(range.start() as u32, range.end() as u32)).collect()
+            }
+        }
+    }
+
+    //Minimum and maximum (None if unbounded) number of iterations for a repetition
+    pub(crate) fn repetition_bounds(kind: & RepetitionKind) -> (u32, Option<u32>) {
+        match kind {
+            RepetitionKind::ZeroOrOne => (0, Some(1)),
+            RepetitionKind::OneOrMore => (1, None),
+            RepetitionKind::ZeroOrMore => (0, None),
+            RepetitionKind::Range(range) => match range {
+                RepetitionRange::AtLeast(n) => (*n, None),
+                RepetitionRange::Bounded(n, m) => (*n, Some(*m)),
+                RepetitionRange::Exactly(n) => (*n, Some(*n)),
+            }
+        }
+    }
+
+    //A lazy repetition only backtracks into the tokens that follow it in the same concatenation.
+    //These are translated as its continuation, which is retried after each extra iteration
+    fn translate_lazy(repeater: & Repetition, continuation: & [Hir], capture_names: & mut HashMap<String, u32>, in_inner_loop: bool) -> Result<(Vec<Token>, Option<u32>), TranslateError> {
+
+        let (mut subset, mut max) = Ehir::translate_hir(repeater.hir.as_ref(), capture_names, true)?;
+
+        let (n, m) = Ehir::repetition_bounds(& repeater.kind);
+
+        if repeater.hir.is_match_empty() {
+            subset = Ehir::stop_when_empty(subset, n);
+        }
+
+        let mut continuation_code = vec![];
+
+        for hir in continuation {
+            let (mut subset, m) = Ehir::translate_hir(hir, capture_names, true)?;
+
+            Ehir::update_max(& mut max, m);
+
+            continuation_code.append(& mut subset);
+        }
+
+        Ok((Ehir::lazy_to_snippet(subset, continuation_code, in_inner_loop, n, m), max))
+    }
+
     fn capturing_to_snippet(ind: u32, snippet: Vec<Token>) -> Vec<Token> {
 
         vec![Token::Block(vec![Token::Capture(ind, snippet)])]
@@ -119,7 +259,21 @@ impl<'r> Ehir<'r> {
         vec![Token::Block(snippet)]
     }
 
-    fn translate_hir(hir: & Hir, capture_names: & mut HashMap<String, u32>, in_inner_loop: bool) -> Result<(Vec<Token>, Option<u32>), String> {
+    fn alternation_to_snippet(branches: Vec<Vec<Token>>, in_inner_loop: bool) -> Vec<Token> {
+        vec![Token::Alternation(branches, Ehir::stop_break(in_inner_loop))]
+    }
+
+    //Keep track of the highest capture index seen so far
+    fn update_max(max: & mut Option<u32>, m: Option<u32>) {
+        if let Some(m) = m {
+            match max {
+                Some(current) if *current >= m => {}
+                _ => *max = Some(m)
+            }
+        }
+    }
+
+    fn translate_hir(hir: & Hir, capture_names: & mut HashMap<String, u32>, in_inner_loop: bool) -> Result<(Vec<Token>, Option<u32>), TranslateError> {
 
         let mut snippet = vec![];
 
@@ -132,49 +286,22 @@ impl<'r> Ehir<'r> {
             HirKind::Literal(literal) => match literal {
                 Literal::Byte(byte) => {
                     snippet = vec![
-                        Ehir::bounds_check(),
+                        Ehir::bounds_check(in_inner_loop),
                         Token::If(Modifier::Not, Decision::Literal(*byte as u32), Ehir::stop_break(in_inner_loop)),
                         Token::Advance];
                 },
                 Literal::Unicode(ch) => {
                     snippet = vec![
-                        Ehir::bounds_check(),
+                        Ehir::bounds_check(in_inner_loop),
                         Token::If(Modifier::Not, Decision::Literal(*ch as u32), Ehir::stop_break(in_inner_loop)),
                         Token::Advance];
                 }
             },
-            HirKind::Class(class) => match class {
-                Class::Unicode(unicode) => {
-
-                    let range_set: Vec<_> = unicode.iter().map(|unicode_range| {
-
-                        if unicode_range.start() == unicode_range.end() {
-                            Range::Single(unicode_range.start() as u32)
-                        } else {
-                            Range::Multiple(unicode_range.start() as u32, unicode_range.end() as u32)
-                        }
-                    }).collect::<Vec<_>>();
-
-                    snippet = vec![
-                        Ehir::bounds_check(),
-                        Token::If(Modifier::Not, Decision::CharacterSet(range_set), Ehir::stop_break(in_inner_loop)),
-                        Token::Advance];
-                },
-                Class::Bytes(bytes) => {
-                    let range_set: Vec<_> = bytes.iter().map(|unicode_range| {
-
-                        if unicode_range.start() == unicode_range.end() {
-                            Range::Single(unicode_range.start() as u32)
-                        } else {
-                            Range::Multiple(unicode_range.start() as u32, unicode_range.end() as u32)
-                        }
-                    }).collect::<Vec<_>>();
-
-                    snippet = vec![
-                        Ehir::bounds_check(),
-                        Token::If(Modifier::Not, Decision::CharacterSet(range_set), Ehir::stop_break(in_inner_loop)),
-                        Token::Advance];
-                }
+            HirKind::Class(class) => {
+                snippet = vec![
+                    Ehir::bounds_check(in_inner_loop),
+                    Token::If(Modifier::Not, Decision::CharacterSet(Ehir::class_to_ranges(class)), Ehir::stop_break(in_inner_loop)),
+                    Token::Advance];
             },
             HirKind::Anchor(anchor) => match anchor {
                 Anchor::EndLine => {
@@ -207,40 +334,39 @@ impl<'r> Ehir<'r> {
             },
             HirKind::Repetition(repeater) => {
                 if !repeater.greedy {
-                    return Err(String::from("Non-greedy repetition is not supported since backtracking is not supported. Please see NativeRegex readme for more details."));
+                    return Ehir::translate_lazy(repeater, & [], capture_names, in_inner_loop);
                 }
 
-                let (subset, m) = Ehir::translate_hir(repeater.hir.as_ref(), capture_names, true)?;
+                let (mut subset, m) = Ehir::translate_hir(repeater.hir.as_ref(), capture_names, true)?;
 
-                if m.is_some() {
-                    if max.is_some() {
-                        if m.unwrap() > max.unwrap() {
-                            max = Some(m.unwrap())
-                        }
-                    } else {
-                        max = m;
-                    }
+                Ehir::update_max(& mut max, m);
+
+                let can_be_empty = repeater.hir.is_match_empty();
+
+                //Without this, an iteration that consumes nothing would repeat forever
+                if can_be_empty {
+                    subset = Ehir::stop_when_empty(subset, Ehir::repetition_bounds(& repeater.kind).0);
                 }
 
                 snippet = match repeater.kind.clone() {
                     RepetitionKind::ZeroOrOne => {
-                        Ehir::bounded_to_snippet(subset, in_inner_loop, 0, 1)
+                        Ehir::bounded_to_snippet(subset, in_inner_loop, can_be_empty, 0, 1)
                     },
                     RepetitionKind::OneOrMore => {
-                        Ehir::unbounded_to_snippet(subset, in_inner_loop, 1)
+                        Ehir::unbounded_to_snippet(subset, in_inner_loop, can_be_empty, 1)
                     },
                     RepetitionKind::ZeroOrMore => {
-                        Ehir::unbounded_to_snippet(subset, in_inner_loop, 0)
+                        Ehir::unbounded_to_snippet(subset, in_inner_loop, can_be_empty, 0)
                     },
                     RepetitionKind::Range(range) => match range {
                         RepetitionRange::AtLeast(n) => {
-                            Ehir::unbounded_to_snippet(subset, in_inner_loop, n)
+                            Ehir::unbounded_to_snippet(subset, in_inner_loop, can_be_empty, n)
                         },
                         RepetitionRange::Bounded(n, m) => {
-                            Ehir::bounded_to_snippet(subset, in_inner_loop, n, m)
+                            Ehir::bounded_to_snippet(subset, in_inner_loop, can_be_empty, n, m)
                         },
                         RepetitionRange::Exactly(n ) => {
-                            Ehir::bounded_to_snippet(subset, in_inner_loop, n, n)
+                            Ehir::bounded_to_snippet(subset, in_inner_loop, can_be_empty, n, n)
                         }
                     },
 
@@ -267,69 +393,203 @@ impl<'r> Ehir<'r> {
                     }
                 };
 
-                if m.is_some() {
-                    if max.is_some() {
-                        if m.unwrap() > max.unwrap() {
-                            max = Some(m.unwrap())
-                        }
-                    } else {
-                        max = m;
-                    }
-                }
+                Ehir::update_max(& mut max, m);
 
 
 
             },
             HirKind::Concat(hirs) => {
-                for hir in hirs {
-                    let (mut subset, m) = Ehir::translate_hir(hir, capture_names, in_inner_loop)?;
+                for (i, hir) in hirs.iter().enumerate() {
+
+                    //A lazy repetition consumes the rest of the concatenation as its continuation
+                    if let HirKind::Repetition(repeater) = hir.kind() {
+                        if !repeater.greedy {
+                            let (mut subset, m) = Ehir::translate_lazy(repeater, & hirs[i + 1..], capture_names, in_inner_loop)?;
 
+                            Ehir::update_max(& mut max, m);
 
-                    if m.is_some() {
-                        if max.is_some() {
-                            if m.unwrap() > max.unwrap() {
-                                max = Some(m.unwrap())
-                            }
-                        } else {
-                            max = m;
+                            snippet.append(& mut subset);
+                            break;
                         }
                     }
 
+                    let (mut subset, m) = Ehir::translate_hir(hir, capture_names, in_inner_loop)?;
+
+
+                    Ehir::update_max(& mut max, m);
+
                     snippet.append(& mut subset);
                 }
             },
-            HirKind::Alternation(_) => {
-                return Err(String::from("Alternation is not supported. Please see NativeRegex readme for more details."));
+            HirKind::Alternation(hirs) => {
+                let mut branches = Vec::with_capacity(hirs.len());
+
+                //Each branch runs inside its own loop, so a failing branch breaks out and the next one is tried
+                for hir in hirs {
+                    let (subset, m) = Ehir::translate_hir(hir, capture_names, true)?;
+
+                    Ehir::update_max(& mut max, m);
+
+                    branches.push(subset);
+                }
+
+                snippet = Ehir::alternation_to_snippet(branches, in_inner_loop);
             }
         }
 
         Ok((snippet, max))
     }
 
-    pub fn translate(regex: & str) -> Result<Ehir, String> {
+    //Emit the tokens for a run of literals, as a single LiteralString if there is more than one
+    fn flush_literals(literal: & mut String, no_match: NoMatch, merged: & mut Vec<Token>) {
+        let length = literal.chars().count();
+
+        match length {
+            0 => {},
+            1 => {
+                merged.push(Token::If(Modifier::Not, Decision::Middle, no_match));
+                merged.push(Token::If(Modifier::Not, Decision::Literal(literal.chars().next().unwrap() as u32), no_match));
+                merged.push(Token::Advance);
+            }
+            _ => {
+                merged.push(Token::If(Modifier::Not, Decision::Middle, no_match));
+                merged.push(Token::If(Modifier::Not, Decision::LiteralString(literal.clone()), no_match));
+                merged.push(Token::AdvanceBy(length as u32));
+            }
+        }
+
+        literal.clear();
+    }
+
+    //Fold runs of single literals into one LiteralString, so the whole run is compared in one step
+    fn merge_literals(tokens: Vec<Token>) -> Vec<Token> {
+
+        //A single literal is a bounds check, a literal check and an advance
+        let literals = (0..tokens.len()).map(|i| match &tokens[i..] {
+            [Token::If(Modifier::Not, Decision::Middle, bounds_check), Token::If(Modifier::Not, Decision::Literal(character), literal_check), Token::Advance, ..] if bounds_check == literal_check => {
+                std::char::from_u32(*character).map(|character| (character, *literal_check))
+            }
+            _ => None
+        }).collect::<Vec<_>>();
+
+        let mut merged = Vec::with_capacity(tokens.len());
+        let mut literal = String::new();
+        let mut no_match = NoMatch::Stop;
+        let mut skip = 0;
+
+        for (i, token) in tokens.into_iter().enumerate() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            if let Some((character, literal_check)) = literals[i] {
+                //Literals that fail in different ways cannot share a check
+                if literal_check != no_match {
+                    Ehir::flush_literals(& mut literal, no_match, & mut merged);
+                }
+
+                literal.push(character);
+                no_match = literal_check;
+                skip = 2;
+                continue;
+            }
+
+            Ehir::flush_literals(& mut literal, no_match, & mut merged);
+
+            merged.push(match token {
+                Token::While(decision, token) => {
+                    Token::While(decision, Box::new(Ehir::merge_literals(vec![*token]).remove(0)))
+                }
+                Token::Capture(index, token_list) => Token::Capture(index, Ehir::merge_literals(token_list)),
+                Token::Block(token_list) => Token::Block(Ehir::merge_literals(token_list)),
+                Token::Checkpoint(token_list, stop_or_break) => Token::Checkpoint(Ehir::merge_literals(token_list), stop_or_break),
+                Token::Alternation(branches, stop_or_break) => {
+                    Token::Alternation(branches.into_iter().map(Ehir::merge_literals).collect(), stop_or_break)
+                }
+                Token::Lazy(iteration, continuation, n, m, stop_or_break) => {
+                    Token::Lazy(Ehir::merge_literals(iteration), Ehir::merge_literals(continuation), n, m, stop_or_break)
+                }
+                token => token
+            });
+        }
 
-        match Parser::new().parse(regex) {
-            Ok(hir) => {
-                let mut map = HashMap::new();
-                let mut ehir_code = Vec::new();
-                let (inner, max) = Ehir::translate_hir(&hir, & mut map, false)?;
+        Ehir::flush_literals(& mut literal, no_match, & mut merged);
 
-                ehir_code.push(Ehir::bounds_check());
+        merged
+    }
 
-                ehir_code.append(& mut Ehir::capturing_to_snippet(0, inner));
+    //Parse the regex into the `regex_syntax` HIR that all translations start from
+    pub fn parse(regex: & str, options: & TranslateOptions) -> Result<Hir, TranslateError> {
+        ParserBuilder::new()
+            .ignore_whitespace(options.ignore_whitespace)
+            .case_insensitive(options.case_insensitive)
+            .multi_line(options.multi_line)
+            .dot_matches_new_line(options.dot_matches_new_line)
+            .swap_greed(options.swap_greed)
+            .unicode(options.unicode)
+            .build()
+            .parse(regex)
+            .map_err(|e| TranslateError::from_syntax(regex, & e))
+    }
 
-                Ok(Ehir {
-                    _regex: regex,
-                    _tokens: ehir_code,
-                    _capture_names: map,
-                    _capture_count: if max.is_some() { max.unwrap() } else { 0 } + 1
-                })
+    //Collect the spans of the literals, classes and repetitions in the AST. The HIR keeps each of these in the same order,
+    //so the tokens translated from them can be traced back to the pattern
+    fn ast_spans(ast: & Ast, spans: & mut Vec<Span>) {
+        match ast {
+            Ast::Literal(literal) => spans.push(Span::from(& literal.span)),
+            Ast::Dot(span) => spans.push(Span::from(span)),
+            Ast::Class(class) => spans.push(Span::from(class.span())),
+            Ast::Repetition(repetition) => {
+                spans.push(Span::from(& repetition.span));
+                Ehir::ast_spans(& repetition.ast, spans);
+            }
+            Ast::Group(group) => Ehir::ast_spans(& group.ast, spans),
+            Ast::Alternation(alternation) => {
+                for ast in & alternation.asts {
+                    Ehir::ast_spans(ast, spans);
+                }
             }
-            Err(e) => {
-                Err(e.to_string())
+            Ast::Concat(concat) => {
+                for ast in & concat.asts {
+                    Ehir::ast_spans(ast, spans);
+                }
             }
+            Ast::Empty(_) | Ast::Flags(_) | Ast::Assertion(_) => {}
+        }
+    }
+
+    fn spans(regex: & str, options: & TranslateOptions) -> Vec<Span> {
+        let mut spans = vec![];
+
+        if let Ok(ast) = ast::parse::ParserBuilder::new().ignore_whitespace(options.ignore_whitespace).build().parse(regex) {
+            Ehir::ast_spans(& ast, & mut spans);
         }
 
+        spans
+    }
+
+    pub fn from_hir<'a>(regex: & 'a str, hir: & Hir, options: & TranslateOptions) -> Result<Ehir<'a>, TranslateError> {
+        let mut map = HashMap::new();
+        let mut ehir_code = Vec::new();
+        let (inner, max) = Ehir::translate_hir(hir, & mut map, false)?;
+
+        ehir_code.append(& mut Ehir::capturing_to_snippet(0, inner));
+
+        Ok(Ehir {
+            _regex: regex,
+            _tokens: Ehir::merge_literals(ehir_code),
+            _capture_names: map,
+            _capture_count: max.unwrap_or(0) + 1,
+            _spans: Ehir::spans(regex, options),
+        })
+    }
+
+    pub fn translate_with_options<'a>(regex: & 'a str, options: & TranslateOptions) -> Result<Ehir<'a>, TranslateError> {
+        Ehir::from_hir(regex, & Ehir::parse(regex, options)?, options)
+    }
 
+    pub fn translate(regex: & str) -> Result<Ehir<'_>, TranslateError> {
+        Ehir::translate_with_options(regex, & TranslateOptions::default())
     }
 }
@@ -0,0 +1,126 @@
+use crate::analysis::PossessiveWarning;
+use regex_syntax::ast;
+use std::fmt;
+
+//A range of bytes within a pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<& ast::Span> for Span {
+    fn from(span: & ast::Span) -> Self {
+        Span {
+            start: span.start.offset,
+            end: span.end.offset,
+        }
+    }
+}
+
+//Why a pattern could not be translated
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranslateError {
+    Syntax { pattern: String, span: Span, message: String }, //The pattern is not a valid regex
+    Unsupported { pattern: String, span: Span, feature: String }, //The pattern is valid, but uses a feature native regexes cannot translate
+    BackendLimit { pattern: String, span: Span, message: String }, //The pattern translates, but exceeds a limit of the generated code
+    PossessiveWarnings { pattern: String, span: Span, warnings: Vec<PossessiveWarning> }, //`deny_possessive_warnings` is set and the pattern has warnings
+}
+
+impl TranslateError {
+
+    pub fn pattern(&self) -> & str {
+        match self {
+            TranslateError::Syntax { pattern, .. } => pattern,
+            TranslateError::Unsupported { pattern, .. } => pattern,
+            TranslateError::BackendLimit { pattern, .. } => pattern,
+            TranslateError::PossessiveWarnings { pattern, .. } => pattern,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            TranslateError::Syntax { span, .. } => *span,
+            TranslateError::Unsupported { span, .. } => *span,
+            TranslateError::BackendLimit { span, .. } => *span,
+            TranslateError::PossessiveWarnings { span, .. } => *span,
+        }
+    }
+
+    //Sort a `regex_syntax` error into a syntax error, an unsupported feature or a limit
+    pub(crate) fn from_syntax(pattern: & str, error: & regex_syntax::Error) -> Self {
+        let pattern = pattern.to_string();
+
+        match error {
+            regex_syntax::Error::Parse(error) => match error.kind() {
+                ast::ErrorKind::UnsupportedLookAround | ast::ErrorKind::UnsupportedBackreference => TranslateError::Unsupported {
+                    pattern,
+                    span: Span::from(error.span()),
+                    feature: error.kind().to_string(),
+                },
+                //Each group nests a block in the generated code, so the parser's nesting limit is also a limit of the backend
+                ast::ErrorKind::CaptureLimitExceeded | ast::ErrorKind::NestLimitExceeded(_) => TranslateError::BackendLimit {
+                    pattern,
+                    span: Span::from(error.span()),
+                    message: error.kind().to_string(),
+                },
+                kind => TranslateError::Syntax {
+                    pattern,
+                    span: Span::from(error.span()),
+                    message: kind.to_string(),
+                },
+            },
+            regex_syntax::Error::Translate(error) => TranslateError::Syntax {
+                pattern,
+                span: Span::from(error.span()),
+                message: error.kind().to_string(),
+            },
+            _ => TranslateError::Syntax {
+                span: Span { start: 0, end: pattern.len() },
+                pattern,
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let heading = match self {
+            TranslateError::Syntax { .. } => "regex parse error",
+            TranslateError::Unsupported { .. } => "unsupported regex feature",
+            TranslateError::BackendLimit { .. } => "regex exceeds backend limit",
+            TranslateError::PossessiveWarnings { .. } => "regex has possessive warnings",
+        };
+
+        let pattern = self.pattern();
+        let span = self.span();
+
+        //Show the line the span starts on, with carets under the part of it that the span covers
+        let line_start = pattern[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = pattern[line_start..].find('\n').map(|i| i + line_start).unwrap_or(pattern.len());
+
+        let indent = pattern[line_start..span.start].chars().count();
+        let carets = pattern[span.start..span.end.min(line_end).max(span.start)].chars().count().max(1);
+
+        writeln!(f, "{}:", heading)?;
+        writeln!(f, "    {}", & pattern[line_start..line_end])?;
+        writeln!(f, "    {}{}", " ".repeat(indent), "^".repeat(carets))?;
+
+        match self {
+            TranslateError::Syntax { message, .. } | TranslateError::BackendLimit { message, .. } => write!(f, "error: {}", message),
+            TranslateError::Unsupported { feature, .. } => write!(f, "error: {}", feature),
+            TranslateError::PossessiveWarnings { warnings, .. } => {
+                for (i, warning) in warnings.iter().enumerate() {
+                    if i != 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "error: {}", warning)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranslateError {}
@@ -0,0 +1,365 @@
+use crate::ehir::{Ehir, Token, Decision, NoMatch, Modifier, AnchorLocation, AnchorType, WordBoundaryType, Range};
+use crate::native_regex::{CaptureNames, NativeRegex};
+use crate::native_regex::character::{Advancer, CharacterInfo, CharacterSet, Previous};
+use crate::vectormap::VectorMap;
+use crate::error::TranslateError;
+use crate::analysis::{literal_prefix, first_characters, match_length, start_anchor};
+use crate::options::TranslateOptions;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+//How control leaves a token, mirroring the generated code
+enum Flow {
+    Next, //Carry on with the next token
+    Break, //Break out of the innermost loop
+    Stop, //Return None from `step`
+}
+
+impl From<& NoMatch> for Flow {
+    fn from(stop_or_break: & NoMatch) -> Self {
+        match stop_or_break {
+            NoMatch::Stop => Flow::Stop,
+            NoMatch::Break => Flow::Break,
+        }
+    }
+}
+
+//The local variables of a generated `step` function
+struct State<'t, 'c> {
+    chars: Advancer<'t>,
+    character: CharacterInfo,
+    captures: & 'c mut VectorMap<(usize, usize)>,
+    counts: Vec<u32>, //Each repetition block declares its own `match_count`, shadowing the one outside it
+    iteration_starts: Vec<usize>, //Likewise each iteration declares its own `iteration_start`
+}
+
+impl<'t, 'c> State<'t, 'c> {
+
+    fn save(&self, indices: & [u32]) -> (Advancer<'t>, CharacterInfo, Vec<Option<(usize, usize)>>) {
+        (self.chars.clone(), self.character, indices.iter().map(|index| self.captures.get(*index as usize).cloned()).collect())
+    }
+
+    fn restore(& mut self, indices: & [u32], saved: & (Advancer<'t>, CharacterInfo, Vec<Option<(usize, usize)>>)) {
+        self.chars = saved.0.clone();
+        self.character = saved.1;
+
+        for (index, capture) in indices.iter().zip(saved.2.iter()) {
+            self.captures.set(*index as usize, *capture);
+        }
+    }
+
+    fn count(&self) -> u32 {
+        *self.counts.last().unwrap()
+    }
+
+    fn is_word_boundary(&self, boundary_type: & WordBoundaryType) -> bool {
+        let is_word = |character: char| match boundary_type {
+            WordBoundaryType::Byte => character.is_ascii() && regex_syntax::is_word_byte(character as u8),
+            WordBoundaryType::Character => regex_syntax::is_word_character(character),
+        };
+
+        let previous = match self.character.previous() {
+            Previous::Start => false,
+            Previous::Character(character) => is_word(character),
+        };
+
+        let current = self.character.current().map(is_word).unwrap_or(false);
+
+        previous != current
+    }
+
+    fn decide(&self, decision: & Decision) -> bool {
+        match decision {
+            Decision::CharacterSet(range_list) => {
+                let character = self.character.current().unwrap() as u32;
+
+                range_list.iter().any(|range| match range {
+                    Range::Single(n) => character == *n,
+                    Range::Multiple(n, m) => character >= *n && character <= *m,
+                })
+            }
+            Decision::Literal(literal) => self.character.current().unwrap() as u32 == *literal,
+            Decision::LiteralString(literal) => self.chars.text_from(& self.character).starts_with(literal.as_str()),
+            Decision::CountEquals(n) => self.count() == *n,
+            Decision::CountLessThan(n) => self.count() < *n,
+            Decision::Anchor(anchor_type, anchor_location) => match (anchor_type, anchor_location) {
+                (AnchorType::Regular, AnchorLocation::Start) => self.character.previous() == Previous::Start,
+                (AnchorType::Newline, AnchorLocation::Start) => {
+                    self.character.previous() == Previous::Character('\n') || self.character.previous() == Previous::Start
+                }
+                (AnchorType::Regular, AnchorLocation::End) => self.character.current().is_none(),
+                (AnchorType::Newline, AnchorLocation::End) => {
+                    self.character.current().is_none() || self.character.current() == Some('\n')
+                }
+            },
+            Decision::WordBoundary(boundary_type) => self.is_word_boundary(boundary_type),
+            Decision::EmptyIteration(n) => self.character.index() == *self.iteration_starts.last().unwrap() && self.count() >= *n,
+            Decision::Middle => self.character.current().is_some(),
+            Decision::Always => true,
+        }
+    }
+
+    fn run_list(& mut self, token_list: & [Token]) -> Flow {
+        for token in token_list {
+            match self.run(token) {
+                Flow::Next => {}
+                flow => return flow
+            }
+        }
+
+        Flow::Next
+    }
+
+    fn run(& mut self, token: & Token) -> Flow {
+        match token {
+            Token::If(modifier, decision, stop_or_break) => {
+                let holds = self.decide(decision);
+
+                if holds == (*modifier == Modifier::Is) {
+                    return Flow::from(stop_or_break);
+                }
+            }
+            Token::While(decision, body) => {
+                while self.decide(decision) {
+                    match self.run(body) {
+                        Flow::Next => {}
+                        Flow::Break => break,
+                        Flow::Stop => return Flow::Stop,
+                    }
+                }
+            }
+            Token::StartCount => {
+                self.counts.push(0);
+            }
+            Token::IncrementCount => {
+                *self.counts.last_mut().unwrap() += 1;
+            }
+            Token::Advance => {
+                self.character = self.chars.advance();
+            }
+            Token::AdvanceBy(n) => {
+                self.character = self.chars.advance_by(*n as usize);
+            }
+            Token::Capture(index, token_list) => {
+                let start = self.character.index();
+
+                match self.run_list(token_list) {
+                    Flow::Next => {}
+                    flow => return flow
+                }
+
+                self.captures.insert(*index as usize, (start, self.character.index()));
+            }
+            Token::Block(token_list) => {
+                let depth = self.counts.len();
+
+                let flow = self.run_list(token_list);
+
+                self.counts.truncate(depth);
+
+                return flow;
+            }
+            Token::Alternation(branches, stop_or_break) => {
+                let mut indices = vec![];
+                token.capture_indices(& mut indices);
+
+                let saved = self.save(& indices);
+
+                for (i, branch) in branches.iter().enumerate() {
+                    if i != 0 {
+                        self.restore(& indices, & saved);
+                    }
+
+                    match self.run_list(branch) {
+                        Flow::Next => return Flow::Next,
+                        Flow::Break => {}
+                        Flow::Stop => return Flow::Stop,
+                    }
+                }
+
+                self.restore(& indices, & saved);
+
+                return Flow::from(stop_or_break);
+            }
+            Token::Checkpoint(token_list, stop_or_break) => {
+                let mut indices = vec![];
+                token.capture_indices(& mut indices);
+
+                let saved = self.save(& indices);
+
+                let depth = self.iteration_starts.len();
+
+                let flow = self.run_list(token_list);
+
+                self.iteration_starts.truncate(depth);
+
+                match flow {
+                    Flow::Next => {}
+                    Flow::Break => {
+                        self.restore(& indices, & saved);
+                        return Flow::from(stop_or_break);
+                    }
+                    Flow::Stop => return Flow::Stop,
+                }
+            }
+            Token::Lazy(iteration, continuation, n, m, stop_or_break) => {
+                let mut indices = vec![];
+
+                for element in continuation {
+                    element.capture_indices(& mut indices);
+                }
+
+                //The count is pushed like any other `match_count`, so the iteration can read it
+                let depth = self.counts.len();
+
+                self.counts.push(0);
+
+                let flow = loop {
+                    //Try the continuation first once the minimum has been reached, only iterating again if it fails
+                    if self.count() >= *n {
+                        let saved = self.save(& indices);
+
+                        match self.run_list(continuation) {
+                            Flow::Next => break Flow::Next,
+                            Flow::Break => {}
+                            Flow::Stop => break Flow::Stop,
+                        }
+
+                        self.restore(& indices, & saved);
+                    }
+
+                    //The iteration also runs at the end of the text, where an iteration that can match empty still counts
+                    //toward the minimum. `stop_when_empty` ends it once it stops making progress
+                    if Some(self.count()) == *m {
+                        break Flow::from(stop_or_break);
+                    }
+
+                    match self.run_list(iteration) {
+                        Flow::Next => {}
+                        Flow::Break => break Flow::from(stop_or_break),
+                        Flow::Stop => break Flow::Stop,
+                    }
+
+                    *self.counts.last_mut().unwrap() += 1;
+                };
+
+                self.counts.truncate(depth);
+
+                return flow;
+            }
+            Token::IterationStart => {
+                self.iteration_starts.push(self.character.index());
+            }
+            Token::Empty => {}
+        }
+
+        Flow::Next
+    }
+}
+
+//Runs the Ehir tokens of a regex directly, without generating code. This gives the same results as the
+//generated code, so regexes only known at runtime can be used anywhere a NativeRegex can
+#[derive(Clone)]
+pub struct EhirInterpreter {
+    tokens: Arc<Vec<Token>>,
+    named_groups: CaptureNames,
+    capture_count: usize,
+    prefix: String,
+    first_characters: Option<CharacterSet>,
+    length: (usize, Option<usize>),
+    anchor: Option<AnchorType>,
+}
+
+impl EhirInterpreter {
+
+    pub fn new(regex: & str) -> Result<Self, TranslateError> {
+        Ok(EhirInterpreter::from_ehir(Ehir::translate(regex)?))
+    }
+
+    //Only the parser options apply, the interpreter always matches possessively
+    pub fn with_options(regex: & str, options: & TranslateOptions) -> Result<Self, TranslateError> {
+        Ok(EhirInterpreter::from_ehir(Ehir::translate_with_options(regex, options)?))
+    }
+
+    pub fn from_ehir(ehir: Ehir) -> Self {
+
+        let prefix = literal_prefix(& ehir);
+
+        let length = match_length(& ehir);
+
+        let anchor = start_anchor(& ehir);
+
+        let first_characters = first_characters(& ehir).filter(|_| prefix.is_empty()).map(|ranges| {
+            let (ascii, above) = CharacterSet::split(& ranges);
+
+            CharacterSet::owned(ascii, above)
+        });
+
+        let named_groups = ehir._capture_names.into_iter().map(|(name, index)| (Cow::Owned(name), index as usize)).collect();
+
+        EhirInterpreter {
+            tokens: Arc::new(ehir._tokens),
+            named_groups,
+            capture_count: ehir._capture_count as usize,
+            prefix,
+            first_characters,
+            length,
+            anchor,
+        }
+    }
+}
+
+impl NativeRegex for EhirInterpreter {
+
+    type Locations = VectorMap<(usize, usize)>;
+
+    fn step(&self, mut chars: Advancer, captures: & mut VectorMap<(usize, usize)>) -> Option<()> {
+
+        let character = chars.advance();
+
+        let mut state = State {
+            chars,
+            character,
+            captures,
+            counts: vec![],
+            iteration_starts: vec![],
+        };
+
+        match state.run_list(& self.tokens) {
+            Flow::Next => Some(()),
+            _ => None
+        }
+    }
+
+    fn capture_names(&self) -> & CaptureNames {
+        &self.named_groups
+    }
+
+    fn capture_count(&self) -> usize {
+        self.capture_count
+    }
+
+    fn literal_prefix(&self) -> &str {
+        self.prefix.as_str()
+    }
+
+    fn first_characters(&self) -> Option<& CharacterSet> {
+        self.first_characters.as_ref()
+    }
+
+    fn min_length(&self) -> usize {
+        self.length.0
+    }
+
+    fn max_length(&self) -> Option<usize> {
+        self.length.1
+    }
+
+    fn is_anchored_start(&self) -> bool {
+        self.anchor == Some(AnchorType::Regular)
+    }
+
+    fn is_anchored_line_start(&self) -> bool {
+        self.anchor == Some(AnchorType::Newline)
+    }
+}
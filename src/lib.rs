@@ -3,4 +3,12 @@ pub mod rust_translate;
 pub mod native_regex;
 mod regexes;
 pub mod ehir;
+pub mod program;
+pub mod analysis;
+pub mod interpreter;
+pub mod differential;
+pub mod options;
+pub mod error;
+#[cfg(feature = "build")]
+pub mod build;
 pub mod vectormap;
@@ -0,0 +1,10 @@
+use crate::native_regex::character::CharacterInfo;
+
+//An entry on the stack of a generated backtracking matcher. Entries are popped until a Retry is found,
+//undoing any capture changes made since it was pushed. `A` is the matcher's `Advancer` or `ByteAdvancer`
+pub enum Backtrack<A> {
+    Retry(usize, A, CharacterInfo), //Resume at the instruction from the saved position
+    Capture(usize, Option<(usize, usize)>), //Restore a capture to the value it had before it was overwritten
+    CaptureStart(usize, usize), //Restore the recorded start of a capture group
+    IterationStart(usize, usize), //Restore the recorded start of a loop's iteration
+}
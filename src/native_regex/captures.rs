@@ -1,17 +1,17 @@
 
-use crate::vectormap::VectorMap;
-use std::collections::HashMap;
+use crate::vectormap::{CaptureLocations, VectorMap};
 use std::ops::Range;
-use crate::native_regex::NativeRegex;
+use crate::native_regex::{CaptureNames, NativeRegex};
 use std::vec::IntoIter;
 
 pub type NativeRegexLocations = VectorMap<(usize, usize)>;
 
+//Generic over the storage so captures from generated regexes keep their fixed size locations
 #[derive(Clone, Debug)]
-pub struct Captures<'t> {
+pub struct Captures<'t, L = NativeRegexLocations> {
     pub text: & 't str,
-    pub locations: NativeRegexLocations,
-    pub named_groups: HashMap<& 'static str, usize>,
+    pub locations: L,
+    pub named_groups: CaptureNames,
     pub count: usize
 }
 
@@ -63,21 +63,19 @@ impl<'t, 'r, R> CaptureMatches<'t, 'r, R>
 impl<'t, 'r, R> Iterator for CaptureMatches<'t, 'r, R>
     where R: NativeRegex {
 
-    type Item = Captures<'t>;
+    type Item = Captures<'t, R::Locations>;
 
-    fn next(&mut self) -> Option<Captures<'t>> {
+    fn next(&mut self) -> Option<Self::Item> {
         if self.last_end > self.text.len() {
             return None;
         }
-        let locations = match self.regex.regex_function(self.text, self.last_end) {
-            None => return None,
-            Some(m) => m
-        };
+        let locations = self.regex.regex_function(self.text, self.last_end)?;
 
         let (start, end) = locations.get(0).unwrap();
 
         if start == end {
-            self.last_end = end+1;
+            //Step over the next character, rather than a byte which may land part way through it
+            self.last_end = end + self.text[*end..].chars().next().map_or(1, char::len_utf8);
 
             if self.last_match == Some(*end) {
                 return self.next()
@@ -92,7 +90,7 @@ impl<'t, 'r, R> Iterator for CaptureMatches<'t, 'r, R>
             text: self.text,
             count: locations.len(),
             locations,
-            named_groups: HashMap::new(),
+            named_groups: self.regex.capture_names().clone(),
         })
 
     }
@@ -106,12 +104,7 @@ impl<'t, 'r, R> Iterator for Matches<'t, 'r, R>
     type Item = Match<'t>;
 
     fn next(&mut self) -> Option<Match<'t>> {
-        match self.capture_match.next() {
-            Some(capture) => {
-                Some(capture.first())
-            }
-            None => None
-        }
+        self.capture_match.next().map(|capture| capture.first())
     }
 
 }
@@ -153,15 +146,10 @@ impl<'t> From<Match<'t>> for Range<usize> {
 }
 
 
-impl<'t> Captures<'t> {
+impl<'t, L: CaptureLocations> Captures<'t, L> {
 
     pub fn get(&self, i: usize) -> Option<Match<'t>> {
-        match self.locations.get(i) {
-            Some((start, end)) => {
-                Some(Match::new(self.text, *start, *end))
-            }
-            None => None
-        }
+        self.locations.get(i).map(|(start, end)| Match::new(self.text, *start, *end))
     }
 
     pub fn first(&self) -> Match<'t> {
@@ -176,14 +164,9 @@ impl<'t> Captures<'t> {
         }
     }
 
-    pub fn iter(& self) -> IntoIter<Option<Match>> {
+    pub fn iter(& self) -> IntoIter<Option<Match<'t>>> {
 
-        let thing: Vec<_> = self.locations.iter().map(|m| {
-            match m {
-                Some((start, end)) => Some(Match::new(self.text, *start, *end)),
-                None => None,
-            }
-        }).collect();
+        let thing: Vec<_> = (0..self.locations.len()).map(|i| self.get(i)).collect();
 
         thing.into_iter()
     }
@@ -192,6 +175,26 @@ impl<'t> Captures<'t> {
         self.count
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    //Copy the locations into a `VectorMap`, the storage shared by all regexes
+    pub fn into_vector_map(self) -> Captures<'t> {
+        let mut locations = VectorMap::new(self.locations.len());
+
+        for index in 0..self.locations.len() {
+            locations.set(index, self.locations.get(index).cloned());
+        }
+
+        Captures {
+            text: self.text,
+            locations,
+            named_groups: self.named_groups,
+            count: self.count,
+        }
+    }
+
     pub fn expand(&self, mut replacement: &str, dst: &mut String) {
         use crate::regexes::CaptureNameRegex;
 
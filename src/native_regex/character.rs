@@ -1,22 +1,35 @@
 
 use Previous::{Start, Character};
+use std::borrow::Cow;
 use std::str::{CharIndices};
 
 //An iterator-like object that advances over a string providing character information via CharacterInfo
 #[derive(Clone)]
 pub struct Advancer<'t> {
+    text: & 't str,
     iter: CharIndices<'t>,
     prev: Previous,
     start: usize,
     length: usize,
 }
 
-//An iterator that iterates over a string and returns an Advancer for each character
+//An Advancer for regexes that only match ASCII, which steps over bytes instead of decoding chars. Bytes above ASCII
+//are given as the char with the same value, which such a regex never matches, so the indices are the same as an Advancer's
+#[derive(Clone)]
+pub struct ByteAdvancer<'t> {
+    text: & 't str,
+    index: usize, //The index of the byte `advance` will return next
+    prev: Previous,
+}
+
+//An iterator that iterates over a string and returns an Advancer for each character, and one for the end of the text
+//where only empty matches are possible
 pub struct AdvancerIterator<'t> {
-    text: & 't [u8],
+    text: & 't str,
     iter: CharIndices<'t>,
     prev: Previous,
-    start: usize
+    start: usize,
+    ended: bool,
 }
 
 //Enum representing the previous character or Start if at the beginning
@@ -37,12 +50,73 @@ pub struct CharacterInfo {
     previous: Previous
 }
 
+//A set of characters that is quick to test, with a bitmap for ASCII and sorted, non-overlapping ranges for everything else
+#[derive(Clone, Debug)]
+pub struct CharacterSet {
+    ascii: u128,
+    ranges: Cow<'static, [(u32, u32)]>, //Borrowed from a static in generated code, owned by regexes built at runtime
+}
+
+impl CharacterSet {
+
+    pub const fn new(ascii: u128, ranges: & 'static [(u32, u32)]) -> Self {
+        CharacterSet {
+            ascii,
+            ranges: Cow::Borrowed(ranges)
+        }
+    }
+
+    pub fn owned(ascii: u128, ranges: Vec<(u32, u32)>) -> Self {
+        CharacterSet {
+            ascii,
+            ranges: Cow::Owned(ranges)
+        }
+    }
+
+    //Split sorted ranges into the ASCII bitmap and the ranges above ASCII, as given to `new`
+    pub fn split(ranges: & [(u32, u32)]) -> (u128, Vec<(u32, u32)>) {
+        let mut ascii = 0u128;
+        let mut above = vec![];
+
+        for (start, end) in ranges.iter().copied() {
+            for character in start..=end.min(127) {
+                ascii |= 1 << character;
+            }
+
+            if end > 127 {
+                above.push((start.max(128), end));
+            }
+        }
+
+        (ascii, above)
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, character: char) -> bool {
+        let character = character as u32;
+
+        if character < 128 {
+            self.ascii & (1 << character) != 0
+        } else {
+            self.ranges.binary_search_by(|(start, end)| {
+                if *end < character {
+                    std::cmp::Ordering::Less
+                } else if *start > character {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            }).is_ok()
+        }
+    }
+}
+
 impl Previous {
     #[inline(always)]
     pub fn unwrap(&self) -> char {
         match self {
             Character(ch) => {
-                ch.clone()
+                *ch
             }
             Start => {
                 panic!("Failed to unwrap Previous.")
@@ -73,12 +147,34 @@ impl CharacterInfo {
 
 impl<'t> Advancer<'t> {
 
-    pub fn prev(&self) -> Previous { self.prev.clone() }
+    pub fn prev(&self) -> Previous { self.prev }
+
+    //The index of the character `advance` will return next
+    #[inline(always)]
+    pub fn index(&self) -> usize { self.start + self.iter.offset() }
+
+    //The text from the given character onwards
+    #[inline(always)]
+    pub fn text_from(&self, character: & CharacterInfo) -> & 't str {
+        &self.text[character.index()..]
+    }
+
+    //Advance past `n` characters, returning the character after them
+    #[inline(always)]
+    pub fn advance_by(& mut self, n: usize) -> CharacterInfo {
+        for _ in 1..n {
+            if let Some((_, character)) = self.iter.next() {
+                self.prev = Character(character);
+            }
+        }
+
+        self.advance()
+    }
 
     #[inline(always)]
     pub fn advance(& mut self) -> CharacterInfo {
 
-        let prev = self.prev.clone();
+        let prev = self.prev;
 
         match self.iter.next() {
             Some((index, character)) => {
@@ -97,33 +193,98 @@ impl<'t> Advancer<'t> {
 
 }
 
-impl<'t> AdvancerIterator<'t> {
+impl<'t> ByteAdvancer<'t> {
 
+    //Start at `start`, which must be a char boundary
     #[inline(always)]
     pub fn new(text: & 't str, start: usize) -> Self {
+        //Only decode the previous character when it is not ASCII
+        let prev = match start.checked_sub(1).map(|index| text.as_bytes()[index]) {
+            None => Start,
+            Some(byte) if byte.is_ascii() => Character(byte as char),
+            Some(_) => Character(text[..start].chars().next_back().unwrap())
+        };
 
-        let bytes = text.as_bytes();
+        ByteAdvancer {
+            text,
+            index: start,
+            prev
+        }
+    }
 
-        let prev = {
-            let mut previous = Start;
+    pub fn prev(&self) -> Previous { self.prev }
 
-            for (ind, ch) in text.char_indices() {
-                if ind == start {
-                    break;
-                }
-                previous = Character(ch);
-            }
-            previous
-        };
+    #[inline(always)]
+    pub fn index(&self) -> usize { self.index }
+
+    #[inline(always)]
+    pub fn text_from(&self, character: & CharacterInfo) -> & 't str {
+        &self.text[character.index()..]
+    }
+
+    #[inline(always)]
+    pub fn advance_by(& mut self, n: usize) -> CharacterInfo {
+        for _ in 1..n {
+            self.advance();
+        }
 
+        self.advance()
+    }
+
+    #[inline(always)]
+    pub fn advance(& mut self) -> CharacterInfo {
+
+        let prev = self.prev;
+
+        match self.text.as_bytes().get(self.index) {
+            Some(byte) => {
+
+                let character = *byte as char;
+
+                self.prev = Character(character);
+
+                self.index += 1;
+
+                CharacterInfo::new(self.index - 1, Some(character), prev)
 
-        unsafe {
-            AdvancerIterator {
-                text: bytes,
-                iter: std::str::from_utf8_unchecked(&bytes[start..]).char_indices(),
-                prev,
-                start
             }
+            None => {
+
+                CharacterInfo::new(self.text.len(), None, prev)
+            }
+        }
+    }
+
+    //The same position as an Advancer that decodes chars
+    pub fn into_advancer(self) -> Advancer<'t> {
+        Advancer {
+            text: self.text,
+            iter: self.text[self.index..].char_indices(),
+            prev: self.prev,
+            start: self.index,
+            length: self.text.len()
+        }
+    }
+
+}
+
+impl<'t> AdvancerIterator<'t> {
+
+    #[inline(always)]
+    pub fn new(text: & 't str, start: usize) -> Self {
+
+        //Decode the previous character backwards from `start`, so starting part way through the text is constant time
+        let prev = match text[..start].chars().next_back() {
+            Some(ch) => Character(ch),
+            None => Start
+        };
+
+        AdvancerIterator {
+            text,
+            iter: text[start..].char_indices(),
+            prev,
+            start,
+            ended: false
         }
     }
 
@@ -137,13 +298,16 @@ impl<'t> Iterator for AdvancerIterator<'t> {
 
         let iterator = self.iter.clone();
 
-        let prev = self.prev.clone();
-
-        let ch = self.iter.next()?.1;
+        let prev = self.prev;
 
-        self.prev = Character(ch);
+        match self.iter.next() {
+            Some((_, ch)) => self.prev = Character(ch),
+            None if self.ended => return None,
+            None => self.ended = true
+        }
 
         Some(Advancer {
+            text: self.text,
             iter: iterator,
             prev,
             start: self.start,
@@ -2,14 +2,17 @@ pub mod captures;
 pub mod character;
 pub mod replacer;
 pub mod native_regex_set;
+pub mod backtrack;
 
 use captures::{Captures, CaptureMatches, Match, Matches};
 use replacer::Replacer;
 use crate::native_regex::captures::NativeRegexLocations;
-use character::{Advancer, AdvancerIterator};
+use character::{Advancer, AdvancerIterator, ByteAdvancer, CharacterSet};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
-use crate::vectormap::VectorMap;
+use std::sync::Arc;
+use crate::vectormap::{CaptureLocations, VectorMap};
 
 
 pub type NativeRegexReturn<'a> = Option<NativeRegexLocations>;
@@ -21,10 +24,27 @@ pub struct Split<'t, 'r, R>
     last: usize
 }
 
+//Match at a single char boundary, stepping over bytes if the regex only matches ASCII. Like `AdvancerIterator`,
+//this includes the end of the text, where only an empty match is possible
+#[inline(always)]
+fn step_at<R: NativeRegex>(regex: & R, text: & str, position: usize, captures: & mut R::Locations) -> Option<()> {
+    if regex.is_ascii() {
+        regex.step_bytes(ByteAdvancer::new(text, position), captures)
+    } else {
+        regex.step(AdvancerIterator::new(text, position).next()?, captures)
+    }
+}
+
+//The names of a regex's capture groups and their indices. Generated regexes borrow their names from the generated code,
+//while regexes built at runtime own theirs
+pub type CaptureNames = HashMap<Cow<'static, str>, usize>;
+
+pub type StepFunction = dyn Fn(Advancer, & mut VectorMap<(usize, usize)>) -> Option<()> + Send + Sync;
+
 #[derive(Clone)]
 pub struct Engine {
-    regex: fn (chars: Advancer, captures: & mut VectorMap<(usize, usize)>) -> Option<()>,
-    named_groups: HashMap<& 'static str, usize>,
+    regex: Arc<StepFunction>,
+    named_groups: CaptureNames,
     capture_count: usize,
 }
 
@@ -34,7 +54,20 @@ impl Engine {
 
 pub trait NativeRegex: Sized {
 
-    fn step(chars: Advancer, captures: & mut VectorMap<(usize, usize)>) -> Option<()>;
+    //Where `step` records capture groups. Generated regexes use a fixed size `ArrayMap`, so matching never allocates
+    type Locations: CaptureLocations;
+
+    fn step(&self, chars: Advancer, captures: & mut Self::Locations) -> Option<()>;
+
+    //`step` over bytes, used by the search instead of `step` when `is_ascii` is true
+    fn step_bytes(&self, chars: ByteAdvancer, captures: & mut Self::Locations) -> Option<()> {
+        self.step(chars.into_advancer(), captures)
+    }
+
+    //Whether the regex only matches ASCII, so the search can use `step_bytes` and skip decoding UTF-8
+    fn is_ascii(&self) -> bool {
+        false
+    }
 
     fn is_word_byte(byte: u8) -> bool {
         regex_syntax::is_word_byte(byte)
@@ -44,30 +77,195 @@ pub trait NativeRegex: Sized {
         regex_syntax::is_word_character(character)
     }
 
-    fn capture_names(&self) -> &HashMap<& 'static str, usize>;
+    fn capture_names(&self) -> & CaptureNames;
 
     fn capture_count(&self) -> usize;
 
-    fn engine(&self) -> Engine {
+    //Literal text that every match starts with, so the search can skip straight to where it occurs
+    fn literal_prefix(&self) -> &str {
+        ""
+    }
+
+    //The characters a match can start with, so the search can skip positions that start with anything else
+    fn first_characters(&self) -> Option<& CharacterSet> {
+        None
+    }
+
+    //The fewest bytes a match can span, so the search can stop once there is less text than this left
+    fn min_length(&self) -> usize {
+        0
+    }
+
+    //The most bytes a match can span, or None if there is no limit
+    fn max_length(&self) -> Option<usize> {
+        None
+    }
+
+    //Whether every match starts at the start of the text, such as `^abc` or `\Aabc`, so the search makes a single attempt
+    fn is_anchored_start(&self) -> bool {
+        false
+    }
+
+    //Whether every match starts at the start of a line, such as `(?m)^abc`, so the search only makes attempts after newlines
+    fn is_anchored_line_start(&self) -> bool {
+        false
+    }
+
+    fn engine(&self) -> Engine
+        where Self: Clone + Send + Sync + 'static {
+
+        let regex = self.clone();
+
+        //Engines share one storage type, so copy the groups out of the regex's own storage. A set sizes the storage
+        //for its largest engine, which may have more groups than fixed size storage holds
         Engine {
-            regex: Self::step,
+            regex: Arc::new(move |chars, captures: & mut VectorMap<(usize, usize)>| {
+                let mut locations = Self::Locations::with_groups(captures.len());
+
+                regex.step(chars, & mut locations)?;
+
+                for index in 0..locations.len().min(captures.len()) {
+                    captures.set(index, locations.get(index).cloned());
+                }
+
+                Some(())
+            }),
             named_groups: self.capture_names().clone(),
             capture_count: self.capture_count(),
         }
     }
 
     #[inline(always)]
-    fn regex_function(&self, str_text: &str, start: usize) -> NativeRegexReturn {
+    fn regex_function(&self, str_text: &str, start: usize) -> Option<Self::Locations> {
 
-        let mut captures = VectorMap::new(self.capture_count());
+        let mut captures = Self::Locations::with_groups(self.capture_count());
 
-        for it in AdvancerIterator::new(str_text, start) {
+        //A match cannot start after the last position with enough text left for it
+        let last = str_text.len().checked_sub(self.min_length())?;
+
+        //Only the start of the text can start a match
+        if self.is_anchored_start() {
+            if start != 0 {
+                return None;
+            }
+
+            return step_at(self, str_text, 0, & mut captures).map(|_| captures);
+        }
+
+        //Only the start of a line can start a match, so jump from newline to newline
+        if self.is_anchored_line_start() {
+            let mut position = start;
+
+            while position <= last {
+                if position == 0 || str_text.as_bytes()[position - 1] == b'\n' {
+                    if step_at(self, str_text, position, & mut captures).is_some() {
+                        return Some(captures);
+                    }
+
+                    captures.clear();
+                }
+
+                match str_text[position..].find('\n') {
+                    Some(offset) => position += offset + 1,
+                    None => break
+                }
+            }
+
+            return None;
+        }
+
+        let prefix = self.literal_prefix();
+
+        //Only positions where the prefix occurs can start a match, so only try those
+        if let Some(first) = prefix.chars().next() {
+            let mut position = start;
+
+            while let Some(offset) = str_text[position..].find(prefix) {
+                position += offset;
+
+                if position > last {
+                    break;
+                }
 
-            match Self::step(it, & mut captures) {
-                Some(_) => {
+                if step_at(self, str_text, position, & mut captures).is_some() {
                     return Some(captures);
                 }
-                None => {}
+
+                captures.clear();
+
+                position += first.len_utf8();
+            }
+
+            return None;
+        }
+
+        //Only build an `Advancer` for positions whose character can start a match
+        if let Some(first_characters) = self.first_characters() {
+            //The characters of an ASCII only regex are ASCII too, so bytes above ASCII are never among them
+            if self.is_ascii() {
+                for (offset, byte) in str_text.as_bytes()[start..].iter().enumerate() {
+                    if start + offset > last {
+                        break;
+                    }
+
+                    if !first_characters.contains(*byte as char) {
+                        continue;
+                    }
+
+                    if self.step_bytes(ByteAdvancer::new(str_text, start + offset), & mut captures).is_some() {
+                        return Some(captures);
+                    }
+
+                    captures.clear();
+                }
+
+                return None;
+            }
+
+            for (offset, character) in str_text[start..].char_indices() {
+                if start + offset > last {
+                    break;
+                }
+
+                if !first_characters.contains(character) {
+                    continue;
+                }
+
+                if step_at(self, str_text, start + offset, & mut captures).is_some() {
+                    return Some(captures);
+                }
+
+                captures.clear();
+            }
+
+            return None;
+        }
+
+        //Every char boundary is a position to try, without decoding the chars. The end of the text is one too, for empty matches
+        if self.is_ascii() {
+            for position in start..=last {
+                if !str_text.is_char_boundary(position) {
+                    continue;
+                }
+
+                if self.step_bytes(ByteAdvancer::new(str_text, position), & mut captures).is_some() {
+                    return Some(captures);
+                }
+
+                captures.clear();
+            }
+
+            return None;
+        }
+
+        for it in AdvancerIterator::new(str_text, start) {
+
+            if it.index() > last {
+                break;
+            }
+
+            if self.step(it, & mut captures).is_some() {
+                return Some(captures);
             }
 
             captures.clear();
@@ -98,7 +296,7 @@ pub trait NativeRegex: Sized {
         }
     }
 
-    fn captures<'t>(&self, text: & 't str) -> Option<Captures<'t>> {
+    fn captures<'t>(&self, text: & 't str) -> Option<Captures<'t, Self::Locations>> {
         match self.regex_function(text, 0) {
             Some(captures) => {
                 Some(Captures {
@@ -115,7 +313,7 @@ pub trait NativeRegex: Sized {
     fn captures_iter<'t, 'r>(& 'r self, text: & 't str) -> CaptureMatches<'t, 'r, Self> {
 
         CaptureMatches {
-            regex: &self,
+            regex: self,
             text,
             last_end: 0,
             last_match: None
@@ -127,7 +325,7 @@ pub trait NativeRegex: Sized {
     }
 
     fn replace<R>(&self, text: &str, mut rep: R) -> String
-    where R: Replacer {
+    where R: Replacer<Self::Locations> {
 
         let mut iter = self.captures_iter(text).enumerate().peekable();
         if iter.peek().is_none() {
@@ -137,7 +335,7 @@ pub trait NativeRegex: Sized {
         let mut new = String::with_capacity(text.len());
         let mut last_match = 0;
 
-        for (i, capture) in iter {
+        for (_, capture) in iter {
             let m = capture.first();
             new.push_str(&text[last_match..m.start()]);
             rep.replace_append(&capture, & mut new);
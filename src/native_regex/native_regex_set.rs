@@ -33,7 +33,7 @@ impl<'t> SetMatches<'t> {
         }
     }
 
-    pub fn iter(&self) -> SetMatchesIterator {
+    pub fn iter(&self) -> SetMatchesIterator<'_, 't> {
         SetMatchesIterator {
             it: self.matches.iter()
         }
@@ -94,21 +94,17 @@ impl NativeRegexSet {
             for (engine_index, engine) in self.engines.iter().enumerate() {
                 if !finished_set.contains(&engine_index) {
 
-                    match (engine.regex)(it.clone(), & mut captures) {
-                        Some(_) => {
-                            finished_set.insert(engine_index); //Flag the engine for removal
+                    if (engine.regex)(it.clone(), & mut captures).is_some() {
+                        finished_set.insert(engine_index); //Flag the engine for removal
 
-                            let caps = Captures {
-                                text,
-                                named_groups: engine.named_groups.clone(),
-                                locations: captures.clone(),
-                                count: engine.capture_count,
-                            };
+                        let caps = Captures {
+                            text,
+                            named_groups: engine.named_groups.clone(),
+                            locations: captures.clone(),
+                            count: engine.capture_count,
+                        };
 
-                            set_matches.matches.insert(engine_index, caps);
-
-                        }
-                        None => {}
+                        set_matches.matches.insert(engine_index, caps);
                     }
                     captures.clear();
                 }
@@ -1,44 +1,47 @@
 
 use std::borrow::Cow;
-use crate::native_regex::captures::Captures;
+use crate::native_regex::captures::{Captures, NativeRegexLocations};
+use crate::vectormap::CaptureLocations;
 
-pub trait Replacer {
+//Generic over the capture storage so each regex hands over its own locations without copying them
+pub trait Replacer<L: CaptureLocations = NativeRegexLocations> {
 
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String);
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String);
 
 }
 
-impl<F, T> Replacer for F
+impl<F, T, L> Replacer<L> for F
     where
-        F: FnMut(&Captures) -> T,
+        F: FnMut(&Captures<L>) -> T,
         T: AsRef<str>,
+        L: CaptureLocations,
 {
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String) {
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String) {
         dst.push_str((*self)(caps).as_ref());
     }
 }
 
-impl<'a> Replacer for &'a str {
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String) {
-        caps.expand(*self, dst);
+impl<L: CaptureLocations> Replacer<L> for &str {
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String) {
+        caps.expand(self, dst);
     }
 
 }
 
-impl<'a> Replacer for &'a String {
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String) {
+impl<L: CaptureLocations> Replacer<L> for &String {
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String) {
         self.as_str().replace_append(caps, dst)
     }
 }
 
-impl Replacer for String {
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String) {
+impl<L: CaptureLocations> Replacer<L> for String {
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String) {
         self.as_str().replace_append(caps, dst)
     }
 }
 
-impl<'a> Replacer for Cow<'a, str> {
-    fn replace_append(&mut self, caps: &Captures, dst: &mut String) {
+impl<'a, L: CaptureLocations> Replacer<L> for Cow<'a, str> {
+    fn replace_append(&mut self, caps: &Captures<L>, dst: &mut String) {
         self.as_ref().replace_append(caps, dst)
     }
 }
@@ -50,8 +53,8 @@ impl<'t> NoExpand<'t> {
     pub fn new(replacement: & 't str) -> Self { NoExpand ( replacement ) }
 }
 
-impl<'t> Replacer for NoExpand<'t> {
-    fn replace_append(&mut self, _: &Captures, dst: &mut String) {
+impl<'t, L: CaptureLocations> Replacer<L> for NoExpand<'t> {
+    fn replace_append(&mut self, _: &Captures<L>, dst: &mut String) {
         dst.push_str(self.0);
     }
 }
@@ -0,0 +1,32 @@
+
+//Options that change how a regex is translated into source code
+#[derive(Debug, Clone)]
+pub struct TranslateOptions {
+    pub backtracking: bool, //Generate a matcher with full backtracking semantics instead of the faster possessive one
+    pub deny_possessive_warnings: bool, //Fail translation if the possessive matcher may not match where a backtracking one would
+    pub range_table_threshold: usize, //Character sets with more ranges than this are looked up in a static table instead of compared inline
+
+    //Default flags for the parser, which inline flags such as `(?i)` can still override
+    pub case_insensitive: bool, //The `i` flag
+    pub multi_line: bool, //The `m` flag, `^` and `$` match at the start and end of lines
+    pub dot_matches_new_line: bool, //The `s` flag
+    pub swap_greed: bool, //The `U` flag, repetitions are lazy unless followed by `?`
+    pub ignore_whitespace: bool, //The `x` flag, whitespace and `#` comments in the pattern are ignored
+    pub unicode: bool, //The `u` flag, classes and word boundaries are Unicode aware. On by default
+}
+
+impl Default for TranslateOptions {
+    fn default() -> Self {
+        TranslateOptions {
+            backtracking: false,
+            deny_possessive_warnings: false,
+            range_table_threshold: 32,
+            case_insensitive: false,
+            multi_line: false,
+            dot_matches_new_line: false,
+            swap_greed: false,
+            ignore_whitespace: false,
+            unicode: true,
+        }
+    }
+}
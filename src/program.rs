@@ -0,0 +1,234 @@
+use crate::ehir::{Ehir, Decision, Modifier, AnchorLocation, AnchorType, WordBoundaryType};
+use crate::error::{Span, TranslateError};
+use regex_syntax::hir::*;
+
+//Counted repetitions are unrolled, so nesting them multiplies the size of the program. Each instruction is an arm of
+//the generated `match`, so larger programs are refused rather than generated
+pub const MAX_INSTRUCTIONS: usize = 10_000;
+
+//A single step of a backtracking program. Targets are indices into the program
+#[derive(Debug)]
+pub enum Instruction {
+    Consume(Decision), //Advance if the current character matches the Literal or CharacterSet, otherwise backtrack
+    Assert(Modifier, Decision), //Continue if the zero-width decision (anchor or word boundary) Is or is Not true, otherwise backtrack
+    Split(usize, usize), //Continue at the first target, saving the second to try if the first fails
+    Jump(usize),
+    CaptureStart(u32), //Record the start of a capture group
+    CaptureEnd(u32), //Record the end of a capture group
+    IterationStart(usize), //Record where the current iteration of the given loop started
+    RequireProgress(usize), //Backtrack if the current iteration of the given loop consumed nothing
+    Match,
+}
+
+//A flat list of instructions used to generate matchers with full backtracking semantics
+#[derive(Debug)]
+pub struct Program {
+    pub _instructions: Vec<Instruction>,
+    pub _loop_count: usize, //Number of loops that use `IterationStart`, each has its own slot
+}
+
+impl Program {
+
+    fn push(& mut self, instruction: Instruction) -> usize {
+        self._instructions.push(instruction);
+        self._instructions.len() - 1
+    }
+
+    fn next(&self) -> usize {
+        self._instructions.len()
+    }
+
+    //Point the second target of a split at `target`
+    fn patch_split(& mut self, index: usize, target: usize) {
+        if let Instruction::Split(_, ref mut second) = self._instructions[index] {
+            *second = target;
+        }
+    }
+
+    //Swap the targets of a split so the second is preferred, used for lazy repetition
+    fn prefer_second(& mut self, index: usize) {
+        if let Instruction::Split(ref mut first, ref mut second) = self._instructions[index] {
+            std::mem::swap(first, second);
+        }
+    }
+
+    //Gives up with None once the program grows past `MAX_INSTRUCTIONS`, before unrolling makes it any bigger
+    fn compile_hir(& mut self, hir: & Hir) -> Option<()> {
+        if self._instructions.len() > MAX_INSTRUCTIONS {
+            return None;
+        }
+
+        match hir.kind() {
+            HirKind::Empty => {},
+            HirKind::Literal(literal) => match literal {
+                Literal::Byte(byte) => {
+                    self.push(Instruction::Consume(Decision::Literal(*byte as u32)));
+                },
+                Literal::Unicode(ch) => {
+                    self.push(Instruction::Consume(Decision::Literal(*ch as u32)));
+                }
+            },
+            HirKind::Class(class) => {
+                self.push(Instruction::Consume(Decision::CharacterSet(Ehir::class_to_ranges(class))));
+            },
+            HirKind::Anchor(anchor) => {
+                let (anchor_type, anchor_location) = match anchor {
+                    Anchor::EndLine => (AnchorType::Newline, AnchorLocation::End),
+                    Anchor::EndText => (AnchorType::Regular, AnchorLocation::End),
+                    Anchor::StartLine => (AnchorType::Newline, AnchorLocation::Start),
+                    Anchor::StartText => (AnchorType::Regular, AnchorLocation::Start),
+                };
+
+                self.push(Instruction::Assert(Modifier::Is, Decision::Anchor(anchor_type, anchor_location)));
+            },
+            HirKind::WordBoundary(boundary) => {
+                let (modifier, boundary_type) = match boundary {
+                    WordBoundary::Unicode => (Modifier::Is, WordBoundaryType::Character),
+                    WordBoundary::Ascii => (Modifier::Is, WordBoundaryType::Byte),
+                    WordBoundary::UnicodeNegate => (Modifier::Not, WordBoundaryType::Character),
+                    WordBoundary::AsciiNegate => (Modifier::Not, WordBoundaryType::Byte),
+                };
+
+                self.push(Instruction::Assert(modifier, Decision::WordBoundary(boundary_type)));
+            },
+            HirKind::Repetition(repeater) => {
+                let (n, m) = Ehir::repetition_bounds(& repeater.kind);
+
+                //The mandatory iterations are unrolled
+                for _ in 0..n {
+                    self.compile_hir(repeater.hir.as_ref())?;
+                }
+
+                match m {
+                    None => {
+                        //A sub-pattern that can match empty is repeated as `(x+)?`, like other regex engines. Only the
+                        //first iteration may be empty, later ones must consume something or the loop would never end
+                        let empty = repeater.hir.is_match_empty();
+
+                        let mut splits = vec![];
+
+                        if empty && n == 0 {
+                            splits.push(self.push(Instruction::Split(self.next() + 1, 0)));
+                            self.compile_hir(repeater.hir.as_ref())?;
+                        }
+
+                        //Loop back to the split after each iteration, leaving through its second target
+                        let split = self.push(Instruction::Split(self.next() + 1, 0));
+                        splits.push(split);
+
+                        let slot = self._loop_count;
+
+                        if empty {
+                            self._loop_count += 1;
+                            self.push(Instruction::IterationStart(slot));
+                        }
+
+                        self.compile_hir(repeater.hir.as_ref())?;
+
+                        if empty {
+                            self.push(Instruction::RequireProgress(slot));
+                        }
+
+                        self.push(Instruction::Jump(split));
+
+                        let end = self.next();
+
+                        for split in splits {
+                            self.patch_split(split, end);
+
+                            if !repeater.greedy {
+                                self.prefer_second(split);
+                            }
+                        }
+                    }
+                    Some(m) => {
+                        //Each optional iteration may be skipped, jumping straight to the end
+                        let mut splits = vec![];
+
+                        for _ in n..m {
+                            splits.push(self.push(Instruction::Split(self.next() + 1, 0)));
+                            self.compile_hir(repeater.hir.as_ref())?;
+                        }
+
+                        let end = self.next();
+
+                        for split in splits {
+                            self.patch_split(split, end);
+
+                            if !repeater.greedy {
+                                self.prefer_second(split);
+                            }
+                        }
+                    }
+                }
+            },
+            HirKind::Group(group) => match group.kind {
+                GroupKind::NonCapturing => {
+                    self.compile_hir(group.hir.as_ref())?;
+                },
+                GroupKind::CaptureIndex(index) | GroupKind::CaptureName { index, .. } => {
+                    self.push(Instruction::CaptureStart(index));
+                    self.compile_hir(group.hir.as_ref())?;
+                    self.push(Instruction::CaptureEnd(index));
+                }
+            },
+            HirKind::Concat(hirs) => {
+                for hir in hirs {
+                    self.compile_hir(hir)?;
+                }
+            },
+            HirKind::Alternation(hirs) => {
+                let mut jumps = vec![];
+
+                for (i, hir) in hirs.iter().enumerate() {
+
+                    //Every branch but the last saves the start of the next branch to try on failure
+                    if i != hirs.len() - 1 {
+                        let split = self.push(Instruction::Split(self.next() + 1, 0));
+
+                        self.compile_hir(hir)?;
+                        jumps.push(self.push(Instruction::Jump(0)));
+
+                        let next = self.next();
+                        self.patch_split(split, next);
+                    } else {
+                        self.compile_hir(hir)?;
+                    }
+                }
+
+                let end = self.next();
+
+                for jump in jumps {
+                    self._instructions[jump] = Instruction::Jump(end);
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    pub fn compile(regex: & str, hir: & Hir) -> Result<Program, TranslateError> {
+        let mut program = Program {
+            _instructions: vec![],
+            _loop_count: 0,
+        };
+
+        program.push(Instruction::CaptureStart(0));
+
+        let complete = program.compile_hir(hir).is_some();
+
+        program.push(Instruction::CaptureEnd(0));
+        program.push(Instruction::Match);
+
+        //The HIR has no spans, so the limit applies to the whole pattern
+        if !complete || program._instructions.len() > MAX_INSTRUCTIONS {
+            return Err(TranslateError::BackendLimit {
+                pattern: regex.to_string(),
+                span: Span { start: 0, end: regex.len() },
+                message: format!("backtracking program exceeds the limit of {} instructions", MAX_INSTRUCTIONS),
+            });
+        }
+
+        Ok(program)
+    }
+}
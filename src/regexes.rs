@@ -1,8 +1,12 @@
+//Translated output kept as generated, so it carries the same lints as any generated regex
+#![allow(clippy::question_mark, clippy::needless_return, clippy::from_over_into)]
+
 
 use crate::native_regex::NativeRegex;
 
+#[derive(Clone)]
 pub struct CaptureNameRegex {
-    named_groups: std::collections::HashMap<& 'static str, usize>
+    named_groups: crate::native_regex::CaptureNames
 }
 
 impl CaptureNameRegex {
@@ -27,9 +31,11 @@ impl Into<crate::native_regex::Engine> for CaptureNameRegex {
 
 impl crate::native_regex::NativeRegex for CaptureNameRegex {
 
+    type Locations = crate::vectormap::ArrayMap<(usize, usize), 3>;
+
     // Function to match regex '\$(\$)?(?:\{([^{}]*)\})?'
-    #[allow(unused_parens, unused_comparisons)]
-    fn step(mut chars: crate::native_regex::character::Advancer, captures: & mut crate::vectormap::VectorMap<(usize, usize)>) -> Option<()> {
+    #[allow(unused_parens)]
+    fn step(&self, mut chars: crate::native_regex::character::Advancer, captures: & mut Self::Locations) -> Option<()> {
 
 
         //Advance to first character & bounds check
@@ -42,7 +48,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
 
         if character.current().is_none() { return None; }
 
-        if (character.current().unwrap() as u32) != 36 { return None; }
+        if character.current() != Some('$') { return None; }
 
         character = chars.advance();
 
@@ -56,7 +62,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
 
                     if character.current().is_none() { return None; }
 
-                    if (character.current().unwrap() as u32) != 36 { break; }
+                    if character.current() != Some('$') { break; }
 
                     character = chars.advance();
 
@@ -86,7 +92,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
 
                     if character.current().is_none() { return None; }
 
-                    if (character.current().unwrap() as u32) != 123 { break; }
+                    if character.current() != Some('{') { break; }
 
                     character = chars.advance();
 
@@ -100,7 +106,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
                             while character.current().is_some() {
                                 if character.current().is_none() { return None; }
 
-                                if ((character.current().unwrap() as u32) < 0 || (character.current().unwrap() as u32) > 122) && (character.current().unwrap() as u32) != 124 && ((character.current().unwrap() as u32) < 126 || (character.current().unwrap() as u32) > 1114111) {
+                                if !matches!(character.current(), Some('\0'..='z' | '|' | '~'..='\u{10ffff}')) {
                                     break;
                                 }
 
@@ -122,7 +128,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
 
                     if character.current().is_none() { return None; }
 
-                    if (character.current().unwrap() as u32) != 125 { break; }
+                    if character.current() != Some('}') { break; }
 
                     character = chars.advance();
 
@@ -149,7 +155,7 @@ impl crate::native_regex::NativeRegex for CaptureNameRegex {
         return Some(())
     }
 
-    fn capture_names(&self) -> &std::collections::HashMap<& 'static str, usize> {
+    fn capture_names(&self) -> & crate::native_regex::CaptureNames {
         &self.named_groups
     }
 
@@ -1,127 +1,139 @@
 
 
 use crate::ehir::{Ehir, Token, Decision, NoMatch, Modifier, AnchorLocation, AnchorType, WordBoundaryType, Range};
+use crate::program::{Program, Instruction};
+use crate::options::TranslateOptions;
+use crate::error::TranslateError;
+use crate::analysis::{possessive_warnings, literal_prefix, first_characters, match_length, start_anchor, is_ascii};
+use crate::native_regex::character::CharacterSet;
 use std::collections::HashMap;
-use std::borrow::Borrow;
 
 
+//A char pattern matching the range, such as `'a'..='z'`
 fn range_to_snippet(range: & Range, code: & mut String) {
 
+    //Debug formatting escapes the char, so any char gives a valid literal
+    let literal = |n: & u32| format!("{:?}", char::from_u32(*n).unwrap());
+
     match range {
         Range::Single(n) => {
-            code.push_str("(character.current().unwrap() as u32) == ");
-            code.push_str(format!("{}", n).as_str());
+            code.push_str(literal(n).as_str());
         }
         Range::Multiple(n, m) => {
-
-            code.push_str("((character.current().unwrap() as u32) >= ");
-            code.push_str(format!("{}", n).as_str());
-            code.push_str(" && (character.current().unwrap() as u32) <= ");
-            code.push_str(format!("{}", m).as_str());
-
-            code.push_str(")");
+            code.push_str(literal(n).as_str());
+            code.push_str("..=");
+            code.push_str(literal(m).as_str());
         }
     }
 }
 
-//Take a single token and convert it into a snippet of Rust code
-fn translate_token(token: & Token, code: & mut String) -> Result<(), String> {
-    match token {
-        Token::If(modifier, decision, stop_or_break) => {
-            code.push_str("if ");
+//Convert a decision into a Rust expression that is true when the decision holds
+fn decision_to_snippet(decision: & Decision, options: & TranslateOptions, code: & mut String) -> Result<(), TranslateError> {
+    match decision {
+        //Large sets, such as Unicode classes, are looked up in a static table rather than compared one range at a time
+        Decision::CharacterSet(range_list) if range_list.len() > options.range_table_threshold => {
+            let mut ranges = range_list.iter().map(|range| match range {
+                Range::Single(n) => (*n, *n),
+                Range::Multiple(n, m) => (*n, *m),
+            }).collect::<Vec<_>>();
 
-            //Decision
-            match decision {
-                Decision::CharacterSet(range_list) => {
-                    let mut range_list = range_list.into_iter();
+            ranges.sort_unstable();
 
-                    range_to_snippet(range_list.next().unwrap(), code);
+            let (ascii, above) = CharacterSet::split(& ranges);
 
-                    for range in range_list {
-                        code.push_str(" || ");
-                        range_to_snippet(range, code);
-                    }
-                }
-                Decision::Literal(character) => {
-                    code.push_str("(character.current().unwrap() as u32) == ");
-                    code.push_str(format!("{}", character).as_str());
+            code.push_str(format!("{{ static CHARACTERS: native_regex_lib::native_regex::character::CharacterSet = native_regex_lib::native_regex::character::CharacterSet::new({:#x}, & {:?}); matches!(character.current(), Some(c) if CHARACTERS.contains(c)) }}", ascii, above).as_str());
+        }
+        //A single pattern, so the current char is only fetched once and the ranges can become a jump table
+        Decision::CharacterSet(range_list) => {
+            let mut range_list = range_list.iter();
 
-                }
-                Decision::LiteralString(_) => {
-                    return Err(String::from("Literal strings not supported yet."));
-                }
-                Decision::CountEquals(n) => {
-                    code.push_str("match_count == ");
-                    code.push_str(format!("{}", n).as_str());
-                }
-                Decision::CountLessThan(n) => {
-                    code.push_str("match_count < ");
-                    code.push_str(format!("{}", n).as_str());
-                }
-                Decision::Anchor(anchor_type, anchor_location) => {
-                    match anchor_location {
-                        AnchorLocation::Start => {
-                            match anchor_type {
-                                AnchorType::Regular => {
-                                    code.push_str("character.previous() == native_regex_lib::native_regex::character::Previous::Start");
-                                }
-                                AnchorType::Newline => {
-                                    code.push_str("character.previous() == native_regex_lib::native_regex::character::Previous::Character('\\n') || character.previous() == native_regex_lib::native_regex::character::Previous::Start");
-                                }
-                            }
+            code.push_str("matches!(character.current(), Some(");
+
+            range_to_snippet(range_list.next().unwrap(), code);
+
+            for range in range_list {
+                code.push_str(" | ");
+                range_to_snippet(range, code);
+            }
+
+            code.push_str("))");
+        }
+        Decision::Literal(character) => {
+            code.push_str("character.current() == Some(");
+            range_to_snippet(& Range::Single(*character), code);
+            code.push(')');
+        }
+        Decision::LiteralString(literal) => {
+            code.push_str("chars.text_from(&character).starts_with(");
+            code.push_str(format!("{:?}", literal).as_str());
+            code.push(')');
+        }
+        Decision::CountEquals(n) => {
+            code.push_str("match_count == ");
+            code.push_str(format!("{}", n).as_str());
+        }
+        Decision::CountLessThan(n) => {
+            code.push_str("match_count < ");
+            code.push_str(format!("{}", n).as_str());
+        }
+        Decision::Anchor(anchor_type, anchor_location) => {
+            match anchor_location {
+                AnchorLocation::Start => {
+                    match anchor_type {
+                        AnchorType::Regular => {
+                            code.push_str("character.previous() == native_regex_lib::native_regex::character::Previous::Start");
                         }
-                        AnchorLocation::End => {
-                            match anchor_type {
-                                AnchorType::Regular => {
-                                    code.push_str("character.current().is_none()");
-                                }
-                                AnchorType::Newline => {
-                                    code.push_str("{ if character.current().is_some() { if character.current().unwrap() != '\\n' { false } else { true } } else { true } }");
-                                }
-                            }
+                        AnchorType::Newline => {
+                            code.push_str("character.previous() == native_regex_lib::native_regex::character::Previous::Character('\\n') || character.previous() == native_regex_lib::native_regex::character::Previous::Start");
                         }
                     }
                 }
-                Decision::WordBoundary(boundary_type) => {
-                    match boundary_type {
-                        WordBoundaryType::Byte => {
-                            code.push_str("{ if character.previous() != native_regex_lib::native_regex::character::Previous::Start && character.current().is_some() {
-    if (Self::is_word_byte(character.previous().unwrap()) || !Self::is_word_byte(character.current().unwrap())) &&
-        (!Self::is_word_byte(character.previous().unwrap()) || Self::is_word_byte(character.current().unwrap())) {
-        false
-    } else {
-        true
-    }
-} else {
-    if character.previous() == native_regex_lib::native_regex::character::Previous::Start && !Self::is_word_byte(character.current().unwrap()) || character.current().is_none() && !Self::is_word_byte(character.previous().unwrap()) {
-        false
-    } else {
-        true
-    }
-} }");
+                AnchorLocation::End => {
+                    match anchor_type {
+                        AnchorType::Regular => {
+                            code.push_str("character.current().is_none()");
                         }
-                        WordBoundaryType::Character => {
-                            code.push_str("{ if character.previous() != native_regex_lib::native_regex::character::Previous::Start && character.current().is_some() {
-    if (Self::is_word_character(character.previous().unwrap()) || !Self::is_word_character(character.current().unwrap())) &&
-        (!Self::is_word_character(character.previous().unwrap()) || Self::is_word_character(character.current().unwrap())) {
-        false
-    } else {
-        true
-    }
-} else {
-    if character.previous() == native_regex_lib::native_regex::character::Previous::Start && !Self::is_word_character(character.current().unwrap()) || character.current().is_none() && !Self::is_word_character(character.previous().unwrap()) {
-        false
-    } else {
-        true
-    }
-} }");
+                        AnchorType::Newline => {
+                            code.push_str("matches!(character.current(), None | Some('\\n'))");
                         }
                     }
                 }
-                Decision::Middle => {
-                    code.push_str("character.current().is_some()");
-                }
             }
+        }
+        Decision::WordBoundary(boundary_type) => {
+            //A boundary is where exactly one of the previous and current characters is a word character
+            code.push_str("{ let is_word = |c: char| ");
+            match boundary_type {
+                WordBoundaryType::Byte => code.push_str("c.is_ascii() && Self::is_word_byte(c as u8);"),
+                WordBoundaryType::Character => code.push_str("Self::is_word_character(c);"),
+            }
+            code.push_str("
+let previous = match character.previous() {
+    native_regex_lib::native_regex::character::Previous::Start => false,
+    native_regex_lib::native_regex::character::Previous::Character(c) => is_word(c),
+};
+previous != character.current().map(is_word).unwrap_or(false) }");
+        }
+        Decision::EmptyIteration(n) => {
+            code.push_str(format!("character.index() == iteration_start && match_count >= {}", n).as_str());
+        }
+        Decision::Middle => {
+            code.push_str("character.current().is_some()");
+        }
+        Decision::Always => {
+            code.push_str("true");
+        }
+    }
+    Ok(())
+}
+
+//Take a single token and convert it into a snippet of Rust code
+fn translate_token(token: & Token, options: & TranslateOptions, code: & mut String) -> Result<(), TranslateError> {
+    match token {
+        Token::If(modifier, decision, stop_or_break) => {
+            code.push_str("if ");
+
+            decision_to_snippet(decision, options, code)?;
 
             //Cheaty invert the logic by using else
             if *modifier == Modifier::Not {
@@ -130,28 +142,22 @@ fn translate_token(token: & Token, code: & mut String) -> Result<(), String> {
 
             //Body
             code.push_str(" { ");
-            code.push_str(match stop_or_break {
-                NoMatch::Stop => {
-                    "return None;"
-                }
-                NoMatch::Break => {
-                    "break;"
-                }
-            });
+            code.push_str(no_match_snippet(stop_or_break));
             code.push_str(" }\n\n");
         }
         Token::While(decision, block) => {
 
-            code.push_str("while ");
-
             match decision {
                 Decision::Middle => {
-                    code.push_str("character.current().is_some() ")
+                    code.push_str("while character.current().is_some() ")
+                }
+                Decision::Always => {
+                    code.push_str("loop ")
                 }
                 _ => { unreachable!() }
             }
 
-            translate_token(block.as_ref(), code)?;
+            translate_token(block.as_ref(), options, code)?;
         }
         Token::StartCount => {
             code.push_str("let mut match_count = 0;\n\n");
@@ -162,6 +168,9 @@ fn translate_token(token: & Token, code: & mut String) -> Result<(), String> {
         Token::Advance => {
             code.push_str("character = chars.advance();\n\n");
         }
+        Token::AdvanceBy(n) => {
+            code.push_str(format!("character = chars.advance_by({});\n\n", n).as_str());
+        }
         Token::Capture(index, token_list) => {
             let index = format!("{}", index);
 
@@ -172,7 +181,7 @@ fn translate_token(token: & Token, code: & mut String) -> Result<(), String> {
 
             //Capture body
             for element in token_list {
-                translate_token(element, code)?;
+                translate_token(element, options, code)?;
             }
 
             //End of capture
@@ -186,30 +195,268 @@ fn translate_token(token: & Token, code: & mut String) -> Result<(), String> {
             code.push_str("{\n\n");
 
             for element in token_list {
-                translate_token(element, code)?;
+                translate_token(element, options, code)?;
             }
 
             code.push_str("}\n\n")
         }
+        Token::Alternation(branches, stop_or_break) => {
+            let mut indices = vec![];
+            token.capture_indices(& mut indices);
+
+            //Save the position and any captures the branches might overwrite
+            code.push_str("{\n\n");
+            save_snippet("alternation", & indices, code);
+
+            code.push_str("let mut alternation_matched = false;\n\n");
+
+            for (i, branch) in branches.iter().enumerate() {
+
+                //Every branch after the first only runs if the previous ones failed, starting from the saved position
+                if i != 0 {
+                    code.push_str("if !alternation_matched {\n\n");
+                    restore_snippet("alternation", & indices, code);
+                }
+
+                code.push_str("loop {\n\n");
+
+                for element in branch {
+                    translate_token(element, options, code)?;
+                }
+
+                code.push_str("alternation_matched = true;\n\nbreak;\n\n}\n\n");
+
+                if i != 0 {
+                    code.push_str("}\n\n");
+                }
+            }
+
+            code.push_str("if !alternation_matched {\n\n");
+            restore_snippet("alternation", & indices, code);
+            code.push_str(no_match_snippet(stop_or_break));
+            code.push_str("\n\n}\n\n}\n\n");
+        }
+        Token::Checkpoint(token_list, stop_or_break) => {
+            let mut indices = vec![];
+            token.capture_indices(& mut indices);
+
+            //Captures recorded by the tokens are only kept if all of them succeed
+            code.push_str("{\n\n");
+            save_snippet("checkpoint", & indices, code);
+
+            code.push_str("let mut checkpoint_matched = false;\n\nloop {\n\n");
+
+            for element in token_list {
+                translate_token(element, options, code)?;
+            }
+
+            code.push_str("checkpoint_matched = true;\n\nbreak;\n\n}\n\nif !checkpoint_matched {\n\n");
+            restore_snippet("checkpoint", & indices, code);
+            code.push_str(no_match_snippet(stop_or_break));
+            code.push_str("\n\n}\n\n}\n\n");
+        }
+        Token::Lazy(iteration, continuation, n, m, stop_or_break) => {
+            let mut indices = vec![];
+
+            for element in continuation {
+                element.capture_indices(& mut indices);
+            }
+
+            code.push_str("{\n\nlet mut match_count = 0;\n\nlet mut lazy_matched = false;\n\nloop {\n\n");
+
+            //Try the continuation first once the minimum has been reached, only iterating again if it fails
+            if *n == 0 {
+                code.push_str("{\n\n");
+            } else {
+                code.push_str(format!("if match_count >= {} {{\n\n", n).as_str());
+            }
+
+            save_snippet("continuation", & indices, code);
+
+            code.push_str("let mut continuation_matched = false;\n\nloop {\n\n");
+
+            for element in continuation {
+                translate_token(element, options, code)?;
+            }
+
+            code.push_str("continuation_matched = true;\n\nbreak;\n\n}\n\nif continuation_matched {\n\nlazy_matched = true;\n\nbreak;\n\n}\n\n");
+            restore_snippet("continuation", & indices, code);
+            code.push_str("}\n\n");
+
+            //Iterate at the end of the text too, so iterations that can match empty count toward the minimum there
+            if let Some(m) = m {
+                code.push_str(format!("if match_count == {} {{ break; }}\n\n", m).as_str());
+            }
+
+            for element in iteration {
+                translate_token(element, options, code)?;
+            }
+
+            code.push_str("match_count += 1;\n\n}\n\nif !lazy_matched {\n\n");
+            code.push_str(no_match_snippet(stop_or_break));
+            code.push_str("\n\n}\n\n}\n\n");
+        }
+        Token::IterationStart => {
+            code.push_str("let iteration_start = character.index();\n\n");
+        }
         Token::Empty => {}
     }
     Ok(())
 }
 
-//Not a token. Just return a bunch of (index, & str) pairs
+fn no_match_snippet(stop_or_break: & NoMatch) -> & 'static str {
+    match stop_or_break {
+        NoMatch::Stop => {
+            "return None;"
+        }
+        NoMatch::Break => {
+            "break;"
+        }
+    }
+}
+
+//Remember the current position and the given captures so they can be restored with `restore_snippet`
+fn save_snippet(name: & str, indices: & [u32], code: & mut String) {
+    code.push_str(format!("let {}_chars = chars.clone();\n\nlet {}_character = character;\n\n", name, name).as_str());
+
+    for index in indices {
+        code.push_str(format!("let capture_{}_saved = captures.get({}).cloned();\n\n", index, index).as_str());
+    }
+}
+
+//Rewind to the position and captures saved by `save_snippet`
+fn restore_snippet(name: & str, indices: & [u32], code: & mut String) {
+    code.push_str(format!("chars = {}_chars.clone();\n\ncharacter = {}_character;\n\n", name, name).as_str());
+
+    for index in indices {
+        code.push_str(format!("captures.set({}, capture_{}_saved);\n\n", index, index).as_str());
+    }
+}
+
+//Not a token. Just return a bunch of (index, & str) pairs, borrowed from the generated code
 fn map_to_snippet(map: & HashMap<String, u32>, code: & mut String) {
     for (name, index) in map {
-        code.push_str(format!("named_groups.insert(\"{}\", {});\n", name, index).as_str());
+        code.push_str(format!("named_groups.insert(std::borrow::Cow::Borrowed(\"{}\"), {});\n", name, index).as_str());
+    }
+}
+
+//The body of a possessive `step` function, generated from the Ehir tokens
+fn tokens_to_snippet(ehir: & Ehir, options: & TranslateOptions, code: & mut String) -> Result<(), TranslateError> {
+    for element in ehir._tokens.iter() {
+        translate_token(element, options, code)?;
     }
+
+    code.push_str("Some(())");
+
+    Ok(())
+}
+
+//The body of a backtracking `step` function. Each instruction becomes an arm of a match on the program counter,
+//and a failed arm pops the stack until it finds somewhere to retry from
+fn program_to_snippet(program: & Program, capture_count: u32, options: & TranslateOptions, code: & mut String) -> Result<(), TranslateError> {
+    code.push_str(format!("let mut capture_starts = [0usize; {}];\n\n", capture_count).as_str());
+    code.push_str(format!("let mut iteration_starts = [0usize; {}];\n\n", program._loop_count).as_str());
+    code.push_str("let mut stack: Vec<native_regex_lib::native_regex::backtrack::Backtrack<_>> = Vec::new();\n\n");
+    code.push_str("let mut pc = 0;\n\nloop {\n\nlet matched = match pc {\n\n");
+
+    for (i, instruction) in program._instructions.iter().enumerate() {
+        code.push_str(format!("{} => ", i).as_str());
+
+        match instruction {
+            Instruction::Consume(decision) => {
+                code.push_str("if character.current().is_some() && (");
+                decision_to_snippet(decision, options, code)?;
+                code.push_str(format!(") {{\n\ncharacter = chars.advance();\n\npc = {};\n\ntrue\n\n}} else {{ false }}", i + 1).as_str());
+            }
+            Instruction::Assert(modifier, decision) => {
+                code.push_str("if ");
+
+                if *modifier == Modifier::Not {
+                    code.push('!');
+                }
+
+                code.push('(');
+                decision_to_snippet(decision, options, code)?;
+                code.push_str(format!(") {{ pc = {}; true }} else {{ false }}", i + 1).as_str());
+            }
+            Instruction::Split(first, second) => {
+                code.push_str(format!("{{\n\nstack.push(native_regex_lib::native_regex::backtrack::Backtrack::Retry({}, chars.clone(), character));\n\npc = {};\n\ntrue\n\n}}", second, first).as_str());
+            }
+            Instruction::Jump(target) => {
+                code.push_str(format!("{{ pc = {}; true }}", target).as_str());
+            }
+            Instruction::CaptureStart(index) => {
+                code.push_str(format!("{{\n\nstack.push(native_regex_lib::native_regex::backtrack::Backtrack::CaptureStart({}, capture_starts[{}]));\n\ncapture_starts[{}] = character.index();\n\npc = {};\n\ntrue\n\n}}", index, index, index, i + 1).as_str());
+            }
+            Instruction::CaptureEnd(index) => {
+                code.push_str(format!("{{\n\nstack.push(native_regex_lib::native_regex::backtrack::Backtrack::Capture({}, captures.get({}).cloned()));\n\ncaptures.insert({}, (capture_starts[{}], character.index()));\n\npc = {};\n\ntrue\n\n}}", index, index, index, index, i + 1).as_str());
+            }
+            Instruction::IterationStart(slot) => {
+                code.push_str(format!("{{\n\nstack.push(native_regex_lib::native_regex::backtrack::Backtrack::IterationStart({}, iteration_starts[{}]));\n\niteration_starts[{}] = character.index();\n\npc = {};\n\ntrue\n\n}}", slot, slot, slot, i + 1).as_str());
+            }
+            Instruction::RequireProgress(slot) => {
+                code.push_str(format!("if character.index() != iteration_starts[{}] {{ pc = {}; true }} else {{ false }}", slot, i + 1).as_str());
+            }
+            Instruction::Match => {
+                code.push_str("return Some(())");
+            }
+        }
+
+        code.push_str(",\n\n");
+    }
+
+    code.push_str("_ => unreachable!()\n\n};\n\n");
+
+    //Undo capture changes until a retry point is found, giving up if there is nowhere left to retry
+    code.push_str("if !matched {
+
+    loop {
+        match stack.pop() {
+            Some(native_regex_lib::native_regex::backtrack::Backtrack::Retry(retry_pc, retry_chars, retry_character)) => {
+                pc = retry_pc;
+                chars = retry_chars;
+                character = retry_character;
+                break;
+            }
+            Some(native_regex_lib::native_regex::backtrack::Backtrack::Capture(index, capture)) => {
+                captures.set(index, capture);
+            }
+            Some(native_regex_lib::native_regex::backtrack::Backtrack::CaptureStart(index, start)) => {
+                capture_starts[index] = start;
+            }
+            Some(native_regex_lib::native_regex::backtrack::Backtrack::IterationStart(index, start)) => {
+                iteration_starts[index] = start;
+            }
+            None => {
+                return None;
+            }
+        }
+    }
+
 }
 
-fn translate_ehir(ehir: & Ehir, struct_name: & str) -> Result<String, String> {
+}");
+
+    Ok(())
+}
+
+//Capture storage for a regex with `count` groups, kept on the stack unless there are too many groups to track in one word
+fn locations_type(count: usize) -> String {
+    if count <= 64 {
+        format!("native_regex_lib::vectormap::ArrayMap<(usize, usize), {}>", count)
+    } else {
+        String::from("native_regex_lib::vectormap::VectorMap<(usize, usize)>")
+    }
+}
+
+fn translate_ehir(ehir: & Ehir, struct_name: & str, options: & TranslateOptions, step_body: & str) -> Result<String, TranslateError> {
     let mut code = String::new();
 
-    code.push_str("pub struct ");
+    code.push_str("#[derive(Clone)]
+pub struct ");
     code.push_str(struct_name);
     code.push_str(" {
-    named_groups: std::collections::HashMap<& 'static str, usize>
+    named_groups: native_regex_lib::native_regex::CaptureNames
 }
 
 impl ");
@@ -239,7 +486,7 @@ impl Into<native_regex_lib::native_regex::Engine> for ");
     code.push_str(" {
 
     fn into(self) -> native_regex_lib::native_regex::Engine {
-        self.engine()
+        native_regex_lib::native_regex::NativeRegex::engine(&self)
     }
 
 }
@@ -248,41 +495,135 @@ impl native_regex_lib::native_regex::NativeRegex for ");
     code.push_str(struct_name);
     code.push_str(" {
 
+    type Locations = ");
+    code.push_str(locations_type(ehir._capture_count as usize).as_str());
+    code.push_str(";
+
     // Function to match regex '");
-    code.push_str(ehir._regex);
+    code.push_str(ehir._regex.replace('\n', "\n    // ").as_str()); //Patterns with `ignore_whitespace` often span several lines
     code.push_str("'
-    #[allow(unused_parens, unused_comparisons)]
+    // Translated with ");
+    code.push_str(format!("{:?}", options).as_str());
+    code.push_str("
+    #[allow(unused_parens, clippy::never_loop)]
     #[inline(always)]
-    fn step(mut chars: native_regex_lib::native_regex::character::Advancer, captures: & mut native_regex_lib::vectormap::VectorMap<(usize, usize)>) -> Option<()> {
+    fn step(&self, mut chars: native_regex_lib::native_regex::character::Advancer, captures: & mut Self::Locations) -> Option<()> {
 
         //Advance to first character & bounds check
         let mut character = chars.advance();
 
         ");
 
-    for element in ehir._tokens.iter() {
-        translate_token(element, & mut code)?;
-    }
+    code.push_str(step_body);
 
     code.push_str("
+    }
+");
+
+    //The same matcher over bytes, which the search uses instead of `step`
+    if is_ascii(ehir) {
+        code.push_str("
+    #[allow(unused_parens, clippy::never_loop)]
+    #[inline(always)]
+    fn step_bytes(&self, mut chars: native_regex_lib::native_regex::character::ByteAdvancer, captures: & mut Self::Locations) -> Option<()> {
+
+        //Advance to first character & bounds check
+        let mut character = chars.advance();
+
+        ");
+
+        code.push_str(step_body);
+
+        code.push_str("
+    }
 
-        Some(())
+    fn is_ascii(&self) -> bool { true }
+");
     }
 
-    fn capture_names(&self) -> &std::collections::HashMap<& 'static str, usize> {
+    code.push_str("
+    fn capture_names(&self) -> & native_regex_lib::native_regex::CaptureNames {
         &self.named_groups
     }
 
     fn capture_count(&self) -> usize { ");
     code.push_str(format!("{}", ehir._capture_count).as_str());
     code.push_str(" }
+");
+
+    let (min_length, max_length) = match_length(ehir);
+
+    code.push_str(format!("
+    fn min_length(&self) -> usize {{ {} }}
+
+    fn max_length(&self) -> Option<usize> {{ {:?} }}
+", min_length, max_length).as_str());
+
+    //Only generated for anchored regexes, the default tries every position
+    match start_anchor(ehir) {
+        Some(AnchorType::Regular) => code.push_str("
+    fn is_anchored_start(&self) -> bool { true }
+"),
+        Some(AnchorType::Newline) => code.push_str("
+    fn is_anchored_line_start(&self) -> bool { true }
+"),
+        None => {}
+    }
 
+    //Only generated when there is a prefix, the default skips nothing
+    let prefix = literal_prefix(ehir);
+
+    if !prefix.is_empty() {
+        code.push_str(format!("
+    fn literal_prefix(&self) -> &str {{ {:?} }}
+", prefix).as_str());
+    } else if let Some(ranges) = first_characters(ehir) {
+        //The prefix is the better filter, so the first characters are only needed without one
+        let (ascii, above) = CharacterSet::split(& ranges);
+
+        code.push_str(format!("
+    fn first_characters(&self) -> Option<& native_regex_lib::native_regex::character::CharacterSet> {{
+        static FIRST_CHARACTERS: native_regex_lib::native_regex::character::CharacterSet = native_regex_lib::native_regex::character::CharacterSet::new({:#x}, & {:?});
+
+        Some(& FIRST_CHARACTERS)
+    }}
+", ascii, above).as_str());
+    }
+
+    code.push_str("
 }");
 
 
     Ok(code)
 }
 
-pub fn translate(regex: & str, identifier_name: & str) -> Result<String, String> {
-    translate_ehir(Ehir::translate(regex)?.borrow(), identifier_name)
+pub fn translate_with_options(regex: & str, identifier_name: & str, options: & TranslateOptions) -> Result<String, TranslateError> {
+    let hir = Ehir::parse(regex, options)?;
+    let ehir = Ehir::from_hir(regex, & hir, options)?;
+
+    let mut step_body = String::new();
+
+    if options.deny_possessive_warnings && !options.backtracking {
+        let warnings = possessive_warnings(& ehir);
+
+        if !warnings.is_empty() {
+            return Err(TranslateError::PossessiveWarnings {
+                pattern: regex.to_string(),
+                span: warnings[0].span(),
+                warnings,
+            });
+        }
+    }
+
+    if options.backtracking {
+        program_to_snippet(& Program::compile(regex, & hir)?, ehir._capture_count, options, & mut step_body)?;
+    } else {
+        tokens_to_snippet(& ehir, options, & mut step_body)?;
+    }
+
+    translate_ehir(& ehir, identifier_name, options, step_body.as_str())
+}
+
+pub fn translate(regex: & str, identifier_name: & str) -> Result<String, TranslateError> {
+    translate_with_options(regex, identifier_name, & TranslateOptions::default())
 }
\ No newline at end of file
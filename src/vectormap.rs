@@ -1,59 +1,103 @@
-use std::iter::Enumerate;
 
 //Half vector and half hash map, this absolute abomination allows super fast insertions, removal and clearing with absolutely no collision detection!
+//Occupancy of the first 64 items is tracked in a single `u64`, so regexes with fewer groups never touch `_overflow`
 #[derive(Debug, Clone)]
 pub struct VectorMap<T> {
     _map: Vec<Option<T>>,
     _state: u64,
+    _overflow: Vec<u64>, //Occupancy of items 64 and up, 64 items per word
 }
 
 impl<T> VectorMap<T> {
 
     #[inline(always)]
     pub fn new(n: usize) -> Self {
-        assert!(n < 64);
-
         //Is there a better way to fill a vector with `n` default values?
         let mut _map = (0..n).map(|_| None).collect::<Vec<_>>();
 
         let _state = 0;
 
+        //Empty for 64 items or fewer, which does not allocate
+        let _overflow = vec![0; n.saturating_sub(1) / 64];
+
         VectorMap {
             _map,
             _state,
+            _overflow,
+        }
+    }
+
+    //Items past the end are never present, rather than a panic
+    #[inline(always)]
+    fn is_set(& self, index: usize) -> bool {
+        if index >= self._map.len() {
+            false
+        } else if index < 64 {
+            (self._state & (1 << index)) != 0
+        } else {
+            (self._overflow[index / 64 - 1] & (1 << (index % 64))) != 0
         }
     }
 
     #[inline(always)]
     pub fn get(& self, index: usize) -> Option<& T> {
-        if (self._state & (1 << index)) != 0  {
+        if self.is_set(index) {
             self._map.get(index).unwrap().as_ref()
         } else {
             None
         }
     }
 
+    //Does nothing for items past the end
     #[inline(always)]
     pub fn insert(& mut self, index: usize, item: T) {
+        if index >= self._map.len() {
+            return;
+        }
+
         self._map[index] = Some(item);
-        self._state = self._state | (1 << index); //Set the nth bit
+
+        //Set the nth bit
+        if index < 64 {
+            self._state |= 1 << index;
+        } else {
+            self._overflow[index / 64 - 1] |= 1 << (index % 64);
+        }
     }
 
     #[inline(always)]
     pub fn remove(& mut self, index: usize) {
-        self._state = self._state & !(1 << index); //Clear the nth bit
+        //Clear the nth bit, items past the end are never set
+        if index < 64.min(self._map.len()) {
+            self._state &= !(1 << index);
+        } else if index < self._map.len() {
+            self._overflow[index / 64 - 1] &= !(1 << (index % 64));
+        }
+    }
+
+    //Insert or remove depending on whether `item` is present, used to restore a previously saved value
+    #[inline(always)]
+    pub fn set(& mut self, index: usize, item: Option<T>) {
+        match item {
+            Some(item) => self.insert(index, item),
+            None => self.remove(index)
+        }
     }
 
     #[inline(always)]
     pub fn clear(& mut self) {
         self._state = 0;
+
+        for word in self._overflow.iter_mut() {
+            *word = 0;
+        }
     }
 
     #[inline(always)]
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
-            _iterator: self._map.iter().enumerate(),
-            _state: self._state,
+            _map: self,
+            _index: 0,
         }
     }
 
@@ -62,11 +106,16 @@ impl<T> VectorMap<T> {
         self._map.len()
     }
 
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self._map.is_empty()
+    }
+
 }
 
 pub struct Iter<'a, T> {
-    _iterator: Enumerate<std::slice::Iter<'a, Option<T>>>,
-    _state: u64,
+    _map: & 'a VectorMap<T>,
+    _index: usize,
 }
 
 impl<'a, T> Iterator for Iter<'a, T>
@@ -75,12 +124,170 @@ impl<'a, T> Iterator for Iter<'a, T>
 
     #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> {
-        let (index, next_item) = self._iterator.next()?;
+        if self._index == self._map.len() {
+            return None;
+        }
 
-        Some(if (self._state & (1 << index)) != 0  {
-            next_item.as_ref()
+        let item = self._map.get(self._index);
+
+        self._index += 1;
+
+        Some(item)
+    }
+}
+
+//The same map with a fixed size of `N` items stored inline, so creating one never allocates. `N` can be at most 64,
+//as the generated code only uses it for regexes with 64 or fewer groups
+#[derive(Debug, Clone)]
+pub struct ArrayMap<T, const N: usize> {
+    _map: [Option<T>; N],
+    _state: u64,
+}
+
+impl<T, const N: usize> ArrayMap<T, N> {
+
+    //Evaluated when `new` is compiled for an `N`, so a map too big for the `u64` state does not compile
+    const FITS_STATE: () = assert!(N <= 64, "ArrayMap holds at most 64 items");
+
+    #[inline(always)]
+    pub fn new() -> Self {
+        let () = Self::FITS_STATE;
+
+        ArrayMap {
+            _map: std::array::from_fn(|_| None),
+            _state: 0,
+        }
+    }
+
+    //Items past the end are never present, rather than a panic
+    #[inline(always)]
+    pub fn get(& self, index: usize) -> Option<& T> {
+        if index < N && (self._state & (1 << index)) != 0 {
+            self._map[index].as_ref()
         } else {
             None
-        })
+        }
+    }
+
+    #[inline(always)]
+    pub fn insert(& mut self, index: usize, item: T) {
+        self._map[index] = Some(item);
+        self._state |= 1 << index;
+    }
+
+    #[inline(always)]
+    pub fn remove(& mut self, index: usize) {
+        if index < N {
+            self._state &= !(1 << index);
+        }
     }
+
+    //Does nothing for items past the end
+    #[inline(always)]
+    pub fn set(& mut self, index: usize, item: Option<T>) {
+        if index >= N {
+            return;
+        }
+
+        match item {
+            Some(item) => self.insert(index, item),
+            None => self.remove(index)
+        }
+    }
+
+    #[inline(always)]
+    pub fn clear(& mut self) {
+        self._state = 0;
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+}
+
+impl<T, const N: usize> Default for ArrayMap<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//Storage for the locations of a regex's capture groups, written by `NativeRegex::step`
+pub trait CaptureLocations: Clone {
+
+    //Storage for `n` groups. Fixed size storage ignores `n`
+    fn with_groups(n: usize) -> Self;
+
+    fn get(& self, index: usize) -> Option<& (usize, usize)>;
+
+    fn insert(& mut self, index: usize, item: (usize, usize));
+
+    fn remove(& mut self, index: usize);
+
+    fn set(& mut self, index: usize, item: Option<(usize, usize)>);
+
+    fn clear(& mut self);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+}
+
+impl CaptureLocations for VectorMap<(usize, usize)> {
+
+    #[inline(always)]
+    fn with_groups(n: usize) -> Self { VectorMap::new(n) }
+
+    #[inline(always)]
+    fn get(& self, index: usize) -> Option<& (usize, usize)> { VectorMap::get(self, index) }
+
+    #[inline(always)]
+    fn insert(& mut self, index: usize, item: (usize, usize)) { VectorMap::insert(self, index, item) }
+
+    #[inline(always)]
+    fn remove(& mut self, index: usize) { VectorMap::remove(self, index) }
+
+    #[inline(always)]
+    fn set(& mut self, index: usize, item: Option<(usize, usize)>) { VectorMap::set(self, index, item) }
+
+    #[inline(always)]
+    fn clear(& mut self) { VectorMap::clear(self) }
+
+    #[inline(always)]
+    fn len(&self) -> usize { VectorMap::len(self) }
+
+}
+
+impl<const N: usize> CaptureLocations for ArrayMap<(usize, usize), N> {
+
+    #[inline(always)]
+    fn with_groups(_: usize) -> Self { ArrayMap::new() }
+
+    #[inline(always)]
+    fn get(& self, index: usize) -> Option<& (usize, usize)> { ArrayMap::get(self, index) }
+
+    #[inline(always)]
+    fn insert(& mut self, index: usize, item: (usize, usize)) { ArrayMap::insert(self, index, item) }
+
+    #[inline(always)]
+    fn remove(& mut self, index: usize) { ArrayMap::remove(self, index) }
+
+    #[inline(always)]
+    fn set(& mut self, index: usize, item: Option<(usize, usize)>) { ArrayMap::set(self, index, item) }
+
+    #[inline(always)]
+    fn clear(& mut self) { ArrayMap::clear(self) }
+
+    #[inline(always)]
+    fn len(&self) -> usize { N }
+
 }
@@ -0,0 +1,19 @@
+use native_regex_lib::analysis::{possessive_warnings, PossessiveWarning};
+use native_regex_lib::ehir::Ehir;
+
+fn warnings(pattern: & str) -> Vec<(String, String)> {
+    possessive_warnings(& Ehir::translate(pattern).unwrap()).into_iter()
+        .map(|PossessiveWarning { repetition, following, .. }| (repetition, following))
+        .collect()
+}
+
+#[test]
+fn possessive_warnings_found() {
+    assert_eq!(warnings(r"[0-9]*[0-9]"), [(String::from("[0-9]*"), String::from("[0-9]"))]);
+    assert_eq!(warnings(r".*foo"), [(String::from(".*"), String::from("foo"))]);
+
+    //Nothing after the repetition can match what it consumes
+    assert!(warnings(r"[a-z]*[0-9]").is_empty());
+    assert!(warnings(r"[0-9]+").is_empty());
+    assert!(warnings(r"a*?a").is_empty());
+}
@@ -0,0 +1,70 @@
+use native_regex_lib::build::generate;
+use std::path::PathBuf;
+
+//Write a manifest into its own directory under the test scratch space
+fn manifest(name: & str, contents: & str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::create_dir_all(& dir).unwrap();
+
+    let path = dir.join("regexes.toml");
+    std::fs::write(& path, contents).unwrap();
+
+    path
+}
+
+fn generate_error(name: & str, contents: & str) -> String {
+    let path = manifest(name, contents);
+
+    generate(& path, path.parent().unwrap()).unwrap_err()
+}
+
+#[test]
+fn generates_module() {
+    let path = manifest("generates_module", r#"
+InlineRegex = { pattern = 'cat' }
+
+[IpRegex]
+pattern = '([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})\.([0-9]{1,3})'
+
+[DigitsRegex]
+pattern = '([0-9]*)([0-9])'
+backtracking = true
+range_table_threshold = 0
+"#);
+
+    let module_path = generate(& path, path.parent().unwrap()).unwrap();
+
+    assert_eq!(module_path, path.with_extension("rs"));
+
+    let code = std::fs::read_to_string(module_path).unwrap();
+
+    assert!(code.contains("pub struct IpRegex"));
+    assert!(code.contains("pub struct DigitsRegex"));
+    assert!(code.contains("pub struct InlineRegex"));
+    assert!(code.contains("range_table_threshold: 0"));
+    assert!(code.find("IpRegex").unwrap() < code.find("DigitsRegex").unwrap());
+}
+
+#[test]
+fn reports_line_and_column() {
+    let error = generate_error("bad_pattern", "[Good]\npattern = 'a'\n\n[Bad]\npattern = 'a(b'\n");
+    assert!(error.ends_with("regexes.toml:5:13: regex parse error:\n    a(b\n     ^\nerror: unclosed group"), "{}", error);
+
+    let error = generate_error("missing_pattern", "[Empty]\nbacktracking = true\n");
+    assert!(error.contains("regexes.toml:1:2: missing 'pattern' for 'Empty'"), "{}", error);
+
+    let error = generate_error("unknown_key", "[Typo]\npattern = 'a'\nbacktraking = true\n");
+    assert!(error.contains("regexes.toml:3:1: unknown key 'backtraking'"), "{}", error);
+
+    let error = generate_error("not_boolean", "[Flag]\npattern = 'a'\nbacktracking = 'yes'\n");
+    assert!(error.contains("regexes.toml:3:16: 'backtracking' must be a boolean"), "{}", error);
+
+    let error = generate_error("negative_threshold", "[Table]\npattern = 'a'\nrange_table_threshold = -1\n");
+    assert!(error.contains("regexes.toml:3:25: 'range_table_threshold' must be a non-negative integer"), "{}", error);
+
+    let error = generate_error("bad_name", "[not-a-name]\npattern = 'a'\n");
+    assert!(error.contains("regexes.toml:1:2: 'not-a-name' is not a valid struct name"), "{}", error);
+
+    let error = generate_error("bad_toml", "[Unclosed\npattern = 'a'\n");
+    assert!(error.contains("regexes.toml:1:"), "{}", error);
+}
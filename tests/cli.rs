@@ -0,0 +1,87 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn native_regex(args: & [& str], stdin: & str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_native-regex"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: & Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: & Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn generates_code() {
+    let from_argument = native_regex(& ["--name", "DateRegex", r"(\d{4})-(\d{2})"], "");
+    assert!(from_argument.status.success());
+    assert!(stdout(& from_argument).contains("pub struct DateRegex"));
+
+    let from_stdin = native_regex(& ["-n", "DateRegex"], "(\\d{4})-(\\d{2})\n");
+    assert_eq!(stdout(& from_stdin), stdout(& from_argument));
+
+    let output_path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli_output.rs");
+    let to_file = native_regex(& ["-n", "DateRegex", "-o", output_path.to_str().unwrap(), r"(\d{4})-(\d{2})"], "");
+    assert!(to_file.status.success());
+    assert_eq!(std::fs::read_to_string(output_path).unwrap(), stdout(& from_argument));
+
+    let leading_dash = native_regex(& ["--", "-?[0-9]+"], "");
+    assert!(leading_dash.status.success());
+    assert!(stdout(& leading_dash).contains("pub struct GeneratedRegex"));
+
+    let with_options = native_regex(& ["-i", "--no-unicode", r"\bcat\b"], "");
+    assert!(with_options.status.success());
+    assert!(stdout(& with_options).contains("case_insensitive: true"));
+    assert!(stdout(& with_options).contains("unicode: false"));
+
+    let with_threshold = native_regex(& ["--range-table-threshold", "0", "[a-c]"], "");
+    assert!(with_threshold.status.success());
+    assert!(stdout(& with_threshold).contains("CHARACTERS.contains"));
+
+    let bad_threshold = native_regex(& ["--range-table-threshold", "many", "[a-c]"], "");
+    assert_eq!(bad_threshold.status.code(), Some(2));
+    assert!(stderr(& bad_threshold).contains("--range-table-threshold needs a number, not 'many'"));
+}
+
+#[test]
+fn check() {
+    let valid = native_regex(& ["--check"], "a+\n[0-9]{2}\n");
+    assert!(valid.status.success());
+    assert!(stdout(& valid).is_empty());
+
+    let invalid = native_regex(& ["--check", "a+", "b("], "");
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(stderr(& invalid).contains("regex parse error:\n    b(\n     ^\nerror: unclosed group"));
+    assert!(!stderr(& invalid).contains("a+"));
+
+    let denied = native_regex(& ["--check", "--deny-possessive-warnings", "[0-9]*[0-9]"], "");
+    assert_eq!(denied.status.code(), Some(1));
+    assert!(native_regex(& ["--check", "--deny-possessive-warnings", "--backtracking", "[0-9]*[0-9]"], "").status.success());
+}
+
+#[test]
+fn ehir() {
+    let output = native_regex(& ["--ehir", "ab"], "");
+    assert!(output.status.success());
+    assert!(stdout(& output).contains("LiteralString"));
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(native_regex(& ["--unknown"], "").status.code(), Some(2));
+    assert_eq!(native_regex(& ["--name"], "").status.code(), Some(2));
+    assert_eq!(native_regex(& [], "").status.code(), Some(2));
+    assert_eq!(native_regex(& ["a", "b"], "").status.code(), Some(2));
+}
@@ -0,0 +1,174 @@
+use native_regex_lib::differential::{compare, compare_pattern, Outcome};
+use native_regex_lib::interpreter::EhirInterpreter;
+use native_regex_lib::native_regex::NativeRegex;
+use native_regex_lib::options::TranslateOptions;
+use regex::RegexBuilder;
+use std::borrow::Cow;
+
+const CORPUS: [& str; 12] = [
+    "",
+    "cat",
+    "a cat and a dog",
+    "GET /index.html HTTP/1.1",
+    "ababac",
+    "2021-04-17 and 1999-12-31",
+    "say \"hi\" and \"bye\"",
+    "<a><bb>",
+    "foo\nbar baz\nqux",
+    "héllo wörld",
+    "ERROR: disk full\nWARN: low memory",
+    "1,2,3; 4,5;",
+];
+
+fn assert_agrees(pattern: & str) {
+    let divergences = compare_pattern(pattern, & CORPUS).unwrap();
+
+    for divergence in divergences.iter() {
+        println!("{}: {}", pattern, divergence);
+    }
+
+    assert!(divergences.is_empty(), "{} diverged from regex", pattern);
+}
+
+#[test]
+fn literals() {
+    assert_agrees("cat");
+    assert_agrees("ERROR: ");
+    assert_agrees("wörld");
+}
+
+#[test]
+fn classes_and_repetition() {
+    assert_agrees(r"[0-9]+");
+    assert_agrees(r"(\d{4})-(\d{2})-(\d{2})");
+    assert_agrees(r"[a-z]+\s[a-z]+");
+    assert_agrees(r"(ab)*c");
+}
+
+#[test]
+fn alternation() {
+    assert_agrees("(cat|dog)");
+    assert_agrees("(GET|POST|PUT) ([^ ]+)");
+    assert_agrees("(?:(a)|(b))+");
+}
+
+#[test]
+fn lazy() {
+    assert_agrees("<.+?>");
+    assert_agrees("\".*?\"");
+    assert_agrees(r"(?:\d+,)+?\d+;");
+}
+
+//Iterations that match empty at the end of the text count toward the minimum of a lazy repetition
+#[test]
+fn lazy_minimum_at_end() {
+    for pattern in [r"(?:a*){2,}?", r"(a*){2,3}?", r"c(?:t*){3}?$"].iter() {
+        assert_agrees(pattern);
+    }
+}
+
+#[test]
+fn anchors_and_boundaries() {
+    assert_agrees(r"(?m)^\w+$");
+    assert_agrees(r"\bbar\b");
+    assert_agrees(r"^ERROR");
+}
+
+#[test]
+fn empty_repetitions() {
+    assert_agrees(r"(a*)*b");
+    assert_agrees(r"(a*)+t");
+    assert_agrees(r"(a*){2,}b");
+    assert_agrees(r"(?:\b)+\w");
+    assert_agrees(r"(a|b*)*c");
+    assert_agrees(r"(a*)*?b");
+    assert_agrees(r"((a*)*b)*a");
+    assert_agrees(r"(?:(?:x*)*y)*a");
+    assert_agrees(r"(?:(?:\b)*\w)+");
+    assert_agrees(r"a((?:b?)*?)*c");
+}
+
+//Empty matches at the end of the text, including the whole of an empty text
+#[test]
+fn empty_matches_at_end() {
+    for pattern in [r"x*", r"$", r"(?m)$", r"\B", r"(a*)*", r"(?-u:x*)"].iter() {
+        assert_agrees(pattern);
+    }
+}
+
+#[test]
+fn captures() {
+    assert_agrees(r"(?P<level>[A-Z]+): (?P<message>[a-z ]+)");
+    assert_agrees(r"(?:(a)b)?a");
+}
+
+//Runtime regexes own their group names rather than leaking them
+#[test]
+fn runtime_names() {
+    let regex = EhirInterpreter::new(r"(?P<level>[A-Z]+): (?P<message>[a-z ]+)").unwrap();
+
+    assert!(regex.capture_names().keys().all(|name| matches!(name, Cow::Owned(_))));
+    assert_eq!(regex.captures("ERROR: disk full").unwrap().name("message").map(|m| m.as_str()), Some("disk full"));
+}
+
+#[test]
+fn many_captures() {
+    assert_agrees(format!(r"(\w){}", r"(\w)?".repeat(79)).as_str());
+    assert_agrees(format!("{}(?P<last>c)", "(x)?".repeat(70)).as_str());
+}
+
+#[test]
+fn parser_options() {
+    let options = TranslateOptions {
+        case_insensitive: true,
+        multi_line: true,
+        dot_matches_new_line: true,
+        ..TranslateOptions::default()
+    };
+
+    for pattern in [r"^(error|warn)\w*:", r"^\w+:.+$", r"[a-z]+ (?-i:[a-z]+)"].iter() {
+        let native = EhirInterpreter::with_options(pattern, & options).unwrap();
+        let regex = RegexBuilder::new(pattern).case_insensitive(true).multi_line(true).dot_matches_new_line(true).build().unwrap();
+
+        assert!(compare(& native, & regex, & CORPUS).is_empty(), "{} diverged from regex", pattern);
+    }
+
+    let ascii = TranslateOptions { unicode: false, ..TranslateOptions::default() };
+    let native = EhirInterpreter::with_options(r"\b\w+\b", & ascii).unwrap();
+    let regex = RegexBuilder::new(r"\b\w+\b").unicode(false).build().unwrap();
+
+    assert!(compare(& native, & regex, & CORPUS).is_empty());
+}
+
+#[test]
+fn reports_shrunk_divergence() {
+    let divergences = compare_pattern(r"[0-9]*[0-9]", & ["abc 123 def"]).unwrap();
+
+    assert!(!divergences.is_empty());
+
+    for divergence in divergences {
+        assert_eq!(divergence.input, "abc 123 def");
+        assert_eq!(divergence.shrunk.chars().count(), 1);
+        assert!(divergence.shrunk.chars().all(|character| character.is_ascii_digit()));
+    }
+}
+
+#[test]
+fn reports_every_operation() {
+    let divergences = compare_pattern(r"a(b|bc)d", & ["abcd"]).unwrap();
+
+    assert_eq!(divergences.len(), 3);
+    assert!(matches!(divergences[0].expected, Outcome::Find(Some((0, 4)))));
+    assert!(matches!(divergences[1].actual, Outcome::Captures(None)));
+    assert!(matches!(divergences[2].actual, Outcome::FindIter(ref spans) if spans.is_empty()));
+}
+
+#[test]
+fn empty_matches_step_over_characters() {
+    let regex = EhirInterpreter::new("x*").unwrap();
+
+    let starts: Vec<_> = regex.find_iter("héllo").map(|m| m.start()).collect();
+
+    //The empty match after 'h' steps over the two byte 'é', and the last one is at the end of the text
+    assert_eq!(starts, [0, 1, 3, 4, 5, 6]);
+}
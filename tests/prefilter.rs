@@ -0,0 +1,187 @@
+use native_regex_lib::analysis::{literal_prefix, first_characters, match_length, start_anchor, is_ascii};
+use native_regex_lib::ehir::{Ehir, AnchorType};
+use native_regex_lib::interpreter::EhirInterpreter;
+use native_regex_lib::native_regex::NativeRegex;
+use native_regex_lib::native_regex::character::CharacterSet;
+use native_regex_lib::rust_translate::translate;
+
+fn prefix(pattern: & str) -> String {
+    literal_prefix(& Ehir::translate(pattern).unwrap())
+}
+
+#[test]
+fn literal_prefixes() {
+    assert_eq!(prefix(r"ERROR: (.*)"), "ERROR: ");
+    assert_eq!(prefix(r"^\bfoo(bar)baz"), "foobarbaz");
+    assert_eq!(prefix(r"wörld[0-9]+"), "wörld");
+    assert_eq!(prefix(r"ab+"), "a");
+    assert_eq!(prefix(r"ab*?c"), "a");
+    assert_eq!(prefix(r"a+b"), "");
+    assert_eq!(prefix(r"(?i)error"), "");
+    assert_eq!(prefix(r"ab|ac"), "");
+    assert_eq!(prefix(r"\d+"), "");
+}
+
+#[test]
+fn prefix_search() {
+    let regex = EhirInterpreter::new(r"ERROR: (\w+)").unwrap();
+
+    let text = "INFO: ok\nERROR: disk\nWARN: low\nERROR ERROR: memory";
+
+    let found: Vec<_> = regex.captures_iter(text).map(|captures| captures.get(1).unwrap().as_str()).collect();
+
+    assert_eq!(found, vec!["disk", "memory"]);
+    assert!(!regex.is_match("ERROR:disk"));
+}
+
+#[test]
+fn generated_prefix() {
+    assert!(translate(r"ERROR: (.*)", "LogRegex").unwrap().contains(r#"fn literal_prefix(&self) -> &str { "ERROR: " }"#));
+    assert!(!translate(r"\d+", "NumberRegex").unwrap().contains("fn literal_prefix"));
+
+    //Only patterns without a prefix get a first character set
+    assert!(translate(r"\d+", "NumberRegex").unwrap().contains("fn first_characters"));
+    assert!(!translate(r"ERROR: (.*)", "LogRegex").unwrap().contains("fn first_characters"));
+}
+
+fn first(pattern: & str) -> Option<Vec<(u32, u32)>> {
+    first_characters(& Ehir::translate(pattern).unwrap())
+}
+
+#[test]
+fn first_character_sets() {
+    assert_eq!(first(r"[A-Z]\w+"), Some(vec![(65, 90)]));
+    assert_eq!(first(r"[0-9]?[$€£]"), Some(vec![(36, 36), (48, 57), (163, 163), (8364, 8364)]));
+    assert_eq!(first(r"(?:x*y)?(a|b)c"), Some(vec![(97, 98), (120, 121)]));
+    assert_eq!(first(r"\bfoo|bar"), Some(vec![(98, 98), (102, 102)]));
+    assert_eq!(first(r"a*"), None);
+    assert_eq!(first(r"(?s).x"), None);
+}
+
+#[test]
+fn character_sets() {
+    let (ascii, above) = CharacterSet::split(& [(48, 57), (120, 200), (8364, 8364)]);
+
+    assert_eq!(above, vec![(128, 200), (8364, 8364)]);
+
+    let set = CharacterSet::owned(ascii, above);
+
+    for character in "0189xyz\u{7f}\u{80}È€".chars() {
+        assert!(set.contains(character), "{:?}", character);
+    }
+
+    for character in "/:awÉ\u{10ffff}".chars() {
+        assert!(!set.contains(character), "{:?}", character);
+    }
+
+    //Generated code borrows its ranges from a static
+    static DIGITS: CharacterSet = CharacterSet::new(0x3ff << 48, & [(0x660, 0x669)]);
+
+    assert!(DIGITS.contains('7') && DIGITS.contains('\u{663}'));
+    assert!(!DIGITS.contains('a') && !DIGITS.contains('\u{66a}'));
+}
+
+#[test]
+fn first_character_search() {
+    let regex = EhirInterpreter::new(r"\d?[$€£](\d+)").unwrap();
+
+    let found: Vec<_> = regex.find_iter("costs €5, 3$10 or £20").map(|m| m.as_str()).collect();
+
+    assert_eq!(found, vec!["€5", "3$10", "£20"]);
+}
+
+fn length(pattern: & str) -> (usize, Option<usize>) {
+    match_length(& Ehir::translate(pattern).unwrap())
+}
+
+#[test]
+fn match_lengths() {
+    assert_eq!(length(r"[0-9]{4}-[0-9]{2}-[0-9]{2}"), (10, Some(10)));
+    assert_eq!(length(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}"), (36, Some(36)));
+    assert_eq!(length(r"(ab|c)?d"), (1, Some(3)));
+    assert_eq!(length(r"wörld"), (6, Some(6)));
+    assert_eq!(length(r"[a€]"), (1, Some(3)));
+    assert_eq!(length(r"a{2,3}?b"), (3, Some(4)));
+    assert_eq!(length(r"a+"), (1, None));
+    assert_eq!(length(r"a*?b"), (1, None));
+    assert_eq!(length(r"^\b$"), (0, Some(0)));
+}
+
+#[test]
+fn min_length_search() {
+    let regex = EhirInterpreter::new(r"[0-9]{4}").unwrap();
+
+    assert_eq!(regex.min_length(), 4);
+    assert_eq!(regex.max_length(), Some(4));
+
+    assert!(!regex.is_match("123"));
+    assert_eq!(regex.find_iter("12 2021 99 1999").map(|m| m.as_str()).collect::<Vec<_>>(), vec!["2021", "1999"]);
+
+    assert!(translate(r"[0-9]{4}", "YearRegex").unwrap().contains("fn min_length(&self) -> usize { 4 }"));
+}
+
+fn anchor(pattern: & str) -> Option<AnchorType> {
+    start_anchor(& Ehir::translate(pattern).unwrap())
+}
+
+#[test]
+fn start_anchors() {
+    assert_eq!(anchor(r"^abc"), Some(AnchorType::Regular));
+    assert_eq!(anchor(r"\A(\w+)"), Some(AnchorType::Regular));
+    assert_eq!(anchor(r"(^a|^b)c"), Some(AnchorType::Regular));
+    assert_eq!(anchor(r"(?m)^#"), Some(AnchorType::Newline));
+    assert_eq!(anchor(r"^a|(?m)^b"), Some(AnchorType::Newline));
+    assert_eq!(anchor(r"^a|b"), None);
+    assert_eq!(anchor(r"(?:^a)?b"), None);
+    assert_eq!(anchor(r"a^"), None);
+}
+
+#[test]
+fn anchored_search() {
+    let text = "# one\ntwo # three\n\n# four";
+
+    for pattern in [r"^# (\w+)", r"\A#", r"(?m)^# (\w+)", r"(?m)^(?:#|t)\w*", r"(?m)^$", r"^$"] {
+        let regex = EhirInterpreter::new(pattern).unwrap();
+        let expected = regex::Regex::new(pattern).unwrap();
+
+        for text in [text, "", "\n#", "x# one"] {
+            let found: Vec<_> = regex.find_iter(text).map(|found| found.range()).collect();
+            let expected: Vec<_> = expected.find_iter(text).map(|found| found.range()).collect();
+
+            assert_eq!(found, expected, "{:?} in {:?}", pattern, text);
+        }
+    }
+}
+
+#[test]
+fn generated_anchor() {
+    assert!(translate(r"^abc", "StartRegex").unwrap().contains("fn is_anchored_start(&self) -> bool { true }"));
+    assert!(translate(r"(?m)^abc", "LineRegex").unwrap().contains("fn is_anchored_line_start(&self) -> bool { true }"));
+    assert!(!translate(r"abc", "AbcRegex").unwrap().contains("fn is_anchored"));
+
+    let regex = EhirInterpreter::new(r"^abc").unwrap();
+
+    assert!(regex.is_anchored_start());
+    assert!(!regex.is_anchored_line_start());
+    assert!(!EhirInterpreter::new(r"abc").unwrap().is_anchored_start());
+}
+
+fn ascii(pattern: & str) -> bool {
+    is_ascii(& Ehir::translate(pattern).unwrap())
+}
+
+#[test]
+fn ascii_patterns() {
+    assert!(ascii(r"[0-9]{4}-[0-9]{2}"));
+    assert!(ascii(r"(?m)^ERROR: ([a-z_]+)$"));
+    assert!(ascii(r"(?-u)\b\w+\b"));
+    assert!(ascii(r"(?:ab|cd)*?e"));
+    assert!(!ascii(r"\w+"));
+    assert!(!ascii(r"[^a]"));
+    assert!(!ascii(r"(?i)k"));
+    assert!(!ascii(r"\b[a-z]+"));
+    assert!(!ascii(r"(?:x|é)+"));
+
+    assert!(translate(r"id=[0-9]+", "IdRegex").unwrap().contains("fn step_bytes"));
+    assert!(!translate(r"\w+", "WordRegex").unwrap().contains("fn step_bytes"));
+}
@@ -0,0 +1,38 @@
+use native_regex_lib::options::TranslateOptions;
+use native_regex_lib::rust_translate::{translate, translate_with_options};
+
+fn with_threshold(pattern: & str, range_table_threshold: usize) -> String {
+    translate_with_options(pattern, "ClassRegex", & TranslateOptions { range_table_threshold, ..TranslateOptions::default() }).unwrap()
+}
+
+#[test]
+fn large_classes_use_tables() {
+    //Unicode classes have hundreds of ranges, which are looked up in a table
+    let table = translate(r"\w+", "WordRegex").unwrap();
+    let inline = with_threshold(r"\w+", usize::MAX);
+
+    assert!(table.contains("Some(c) if CHARACTERS.contains(c)"));
+    assert!(!inline.contains("CHARACTERS.contains"));
+    assert!(table.len() < inline.len(), "{} bytes with tables, {} without", table.len(), inline.len());
+
+    //Small classes keep their inline comparisons
+    assert!(!translate(r"[a-z0-9_]+", "NameRegex").unwrap().contains("CHARACTERS.contains"));
+    assert!(with_threshold(r"[a-z0-9_]+", 2).contains("CHARACTERS.contains"));
+}
+
+#[test]
+fn backtracking_tables() {
+    let options = TranslateOptions { backtracking: true, ..TranslateOptions::default() };
+
+    assert!(translate_with_options(r"(\p{L}+)\s", "LetterRegex", & options).unwrap().contains("CHARACTERS.contains"));
+}
+
+#[test]
+fn small_classes_use_patterns() {
+    let code = translate(r#"[a-z_]['\\\n"é\u{301}]x$"#, "PatternRegex").unwrap();
+
+    assert!(code.contains("matches!(character.current(), Some('_' | 'a'..='z'))"), "{}", code);
+    assert!(code.contains(r#"matches!(character.current(), Some('\n' | '"' | '\'' | '\\' | 'é' | '\u{301}'))"#), "{}", code);
+    assert!(code.contains("character.current() == Some('x')"), "{}", code);
+    assert!(!code.contains("as u32"));
+}
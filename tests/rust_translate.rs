@@ -0,0 +1,33 @@
+use native_regex_lib::ehir::{Decision, Ehir, Token};
+use native_regex_lib::rust_translate::translate;
+
+//Collect the merged literals anywhere in the tokens
+fn literal_strings(token_list: & [Token], literals: & mut Vec<String>) {
+    for token in token_list {
+        match token {
+            Token::If(_, Decision::LiteralString(literal), _) => literals.push(literal.clone()),
+            Token::Capture(_, inner) | Token::Block(inner) | Token::Checkpoint(inner, _) => literal_strings(inner, literals),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn merged_literals() {
+    let mut literals = vec![];
+    literal_strings(& Ehir::translate("hello[0-9]wörld").unwrap()._tokens, & mut literals);
+
+    assert_eq!(literals, ["hello", "wörld"]);
+
+    //Each run of literals is compared with a single `starts_with` in each step function, rather than a comparison per character
+    let code = translate("hello", "HelloRegex").unwrap();
+    let step_bytes = code.find("fn step_bytes").unwrap();
+
+    for step in & [& code[.. step_bytes], & code[step_bytes ..]] {
+        assert_eq!(step.matches("starts_with(").count(), 1);
+        assert_eq!(step.matches(r#"starts_with("hello")"#).count(), 1);
+    }
+
+    //A single literal is compared on its own
+    assert_eq!(translate("h[0-9]", "HRegex").unwrap().matches("starts_with(").count(), 0);
+}
@@ -0,0 +1,87 @@
+use native_regex_lib::error::{Span, TranslateError};
+use native_regex_lib::options::TranslateOptions;
+use native_regex_lib::rust_translate::{translate, translate_with_options};
+
+#[test]
+fn syntax_errors() {
+    let error = translate("a(b", "Bad").unwrap_err();
+
+    assert!(matches!(error, TranslateError::Syntax { .. }));
+    assert_eq!(error.span(), Span { start: 1, end: 2 });
+    assert_eq!(error.to_string(), "regex parse error:\n    a(b\n     ^\nerror: unclosed group");
+
+    let error = translate("wörld[z-a]", "Bad").unwrap_err();
+
+    assert!(matches!(error, TranslateError::Syntax { .. }));
+    assert_eq!(error.to_string(), "regex parse error:\n    wörld[z-a]\n          ^^^\nerror: invalid character class range, the start must be <= the end");
+}
+
+#[test]
+fn unsupported_features() {
+    for pattern in [r"a(?=b)", r"(a)\1"].iter() {
+        let error = translate(pattern, "Bad").unwrap_err();
+
+        assert!(matches!(error, TranslateError::Unsupported { .. }), "{}", error);
+        assert_eq!(error.pattern(), *pattern);
+    }
+}
+
+#[test]
+fn backend_limits() {
+    let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+
+    assert!(translate(nested(250).as_str(), "Limit").is_ok());
+
+    let pattern = nested(251);
+    let error = translate(pattern.as_str(), "Limit").unwrap_err();
+
+    assert!(matches!(error, TranslateError::BackendLimit { .. }), "{}", error);
+    assert_eq!(error.span(), Span { start: 250, end: 253 });
+}
+
+#[test]
+fn backtracking_limits() {
+    let options = TranslateOptions { backtracking: true, ..TranslateOptions::default() };
+
+    assert!(translate_with_options(r"a{1000}", "Limit", & options).is_ok());
+
+    //Nested counted repetitions are unrolled into far more instructions than the generated code can hold
+    for pattern in [r"(?:a{100}){100}", r"(?:(?:a{1000}){1000}){1000}"].iter() {
+        let error = translate_with_options(pattern, "Limit", & options).unwrap_err();
+
+        assert!(matches!(error, TranslateError::BackendLimit { .. }), "{}", error);
+        assert_eq!(error.span(), Span { start: 0, end: pattern.len() });
+    }
+
+    //The possessive matcher loops instead of unrolling
+    assert!(translate(r"(?:a{100}){100}", "Limit").is_ok());
+}
+
+#[test]
+fn possessive_warnings() {
+    let options = TranslateOptions { deny_possessive_warnings: true, ..TranslateOptions::default() };
+
+    match translate_with_options("[0-9]*[0-9]", "Digits", & options).unwrap_err() {
+        TranslateError::PossessiveWarnings { warnings, span, .. } => {
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(span, Span { start: 0, end: 11 });
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+
+    //The span covers the repetition and what follows it, not the whole pattern
+    let error = translate_with_options("id: (?:x|\\d+)(?i)ab*BC", "Id", & options).unwrap_err();
+
+    match & error {
+        TranslateError::PossessiveWarnings { warnings, span, .. } => {
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].repetition_span, Span { start: 18, end: 20 });
+            assert_eq!(warnings[0].following_span, Span { start: 20, end: 21 });
+            assert_eq!(*span, Span { start: 18, end: 21 });
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+
+    assert!(error.to_string().starts_with("regex has possessive warnings:\n    id: (?:x|\\d+)(?i)ab*BC\n                      ^^^\n"), "{}", error);
+}
+
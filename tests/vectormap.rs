@@ -0,0 +1,65 @@
+use native_regex_lib::vectormap::{ArrayMap, VectorMap};
+
+#[test]
+fn small_and_large_maps() {
+    for n in [1, 63, 64, 65, 128, 129, 200].iter() {
+        let mut map = VectorMap::new(*n);
+
+        for index in (0..*n).step_by(3) {
+            map.insert(index, index);
+        }
+
+        for (index, item) in map.iter().enumerate() {
+            assert_eq!(item.copied(), if index % 3 == 0 { Some(index) } else { None });
+        }
+
+        assert_eq!(map.iter().count(), *n);
+
+        map.remove(n - 1);
+        assert_eq!(map.get(n - 1), None);
+
+        map.set(n - 1, Some(7));
+        assert_eq!(map.get(n - 1), Some(& 7));
+
+        map.clear();
+        assert!(map.iter().all(|item| item.is_none()));
+
+        //Items past the end are never present
+        map.insert(n + 70, 1);
+        map.set(n + 70, Some(1));
+        map.remove(n + 70);
+        assert_eq!(map.get(n + 70), None);
+        assert_eq!(map.get(*n), None);
+    }
+}
+
+#[test]
+fn array_map() {
+    let mut map = ArrayMap::<usize, 64>::new();
+
+    assert_eq!(map.len(), 64);
+
+    map.insert(0, 1);
+    map.insert(63, 2);
+    assert_eq!(map.get(0), Some(& 1));
+    assert_eq!(map.get(63), Some(& 2));
+    assert_eq!(map.get(1), None);
+
+    map.remove(0);
+    assert_eq!(map.get(0), None);
+
+    map.set(0, Some(3));
+    assert_eq!(map.get(0), Some(& 3));
+
+    map.clear();
+    assert_eq!(map.get(0), None);
+    assert_eq!(map.get(63), None);
+
+    //Items past the end are never present
+    let mut small = ArrayMap::<usize, 3>::new();
+
+    small.set(70, Some(1));
+    small.remove(70);
+    assert_eq!(small.get(64), None);
+    assert_eq!(small.get(70), None);
+}